@@ -0,0 +1,217 @@
+//! Bookmark endpoints.
+
+use reqwest::Url;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+use crate::futures::GetRequest;
+use crate::paginate::{Offset, Page, PagedStream};
+use crate::{
+    BookmarkOptions, BookmarkTags, BookmarkedWork, IllustProfile, NovelProfile, PixivClient,
+    UserBookmarks, Visibility,
+};
+
+impl Visibility {
+    pub(crate) fn restrict(self) -> u8 {
+        match self {
+            Visibility::Public => 0,
+            Visibility::Private => 1,
+        }
+    }
+
+    pub(crate) fn rest(self) -> &'static str {
+        match self {
+            Visibility::Public => "show",
+            Visibility::Private => "hide",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AddIllustBookmark<'a> {
+    illust_id: String,
+    restrict: u8,
+    comment: &'a str,
+    tags: &'a [String],
+}
+
+#[derive(Serialize)]
+struct AddNovelBookmark<'a> {
+    novel_id: String,
+    restrict: u8,
+    comment: &'a str,
+    tags: &'a [String],
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct AddedIllustBookmark {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    last_bookmark_id: Option<i64>,
+}
+
+impl PixivClient {
+    /// Bookmarks an illust, returning the ID of the created bookmark.
+    ///
+    /// If the illust is already bookmarked, its bookmark is updated with
+    /// `options` and `None` is returned.
+    pub async fn add_bookmark(
+        &self,
+        illust_id: i32,
+        options: &BookmarkOptions,
+    ) -> crate::Result<Option<i64>> {
+        let added: AddedIllustBookmark = self
+            .post(self.url("/ajax/illusts/bookmarks/add"))
+            .json(&AddIllustBookmark {
+                illust_id: illust_id.to_string(),
+                restrict: options.restrict.restrict(),
+                comment: &options.comment,
+                tags: &options.tags,
+            })
+            .await?;
+        Ok(added.last_bookmark_id)
+    }
+
+    /// Replaces the visibility, comment and tags of the bookmark on an illust.
+    ///
+    /// Pixiv edits a bookmark by adding it again, so this is
+    /// [`PixivClient::add_bookmark`] without the bookmark ID.
+    pub async fn edit_bookmark(
+        &self,
+        illust_id: i32,
+        options: &BookmarkOptions,
+    ) -> crate::Result<()> {
+        self.add_bookmark(illust_id, options).await?;
+        Ok(())
+    }
+
+    /// Removes a bookmark on an illust.
+    pub async fn delete_bookmark(&self, bookmark_id: i64) -> crate::Result<()> {
+        let _: IgnoredAny = self
+            .post(self.url("/ajax/illusts/bookmarks/delete"))
+            .form(&[("bookmark_id", bookmark_id.to_string())])
+            .await?;
+        Ok(())
+    }
+
+    /// Bookmarks a novel, returning the ID of the created bookmark.
+    ///
+    /// If the novel is already bookmarked, its bookmark is updated with
+    /// `options` and `None` is returned.
+    pub async fn add_novel_bookmark(
+        &self,
+        novel_id: i32,
+        options: &BookmarkOptions,
+    ) -> crate::Result<Option<i64>> {
+        let added: Option<String> = self
+            .post(self.url("/ajax/novels/bookmarks/add"))
+            .json(&AddNovelBookmark {
+                novel_id: novel_id.to_string(),
+                restrict: options.restrict.restrict(),
+                comment: &options.comment,
+                tags: &options.tags,
+            })
+            .await?;
+        Ok(added.and_then(|id| id.parse().ok()))
+    }
+
+    /// Replaces the visibility, comment and tags of the bookmark on a novel.
+    ///
+    /// Pixiv edits a bookmark by adding it again, so this is
+    /// [`PixivClient::add_novel_bookmark`] without the bookmark ID.
+    pub async fn edit_novel_bookmark(
+        &self,
+        novel_id: i32,
+        options: &BookmarkOptions,
+    ) -> crate::Result<()> {
+        self.add_novel_bookmark(novel_id, options).await?;
+        Ok(())
+    }
+
+    /// Removes a bookmark on a novel.
+    pub async fn delete_novel_bookmark(&self, bookmark_id: i64) -> crate::Result<()> {
+        let _: IgnoredAny = self
+            .post(self.url("/ajax/novels/bookmarks/delete"))
+            .form(&[
+                ("del", "1".to_string()),
+                ("book_id", bookmark_id.to_string()),
+            ])
+            .await?;
+        Ok(())
+    }
+
+    /// Get the bookmarked illusts and mangas of an user as a stream.
+    ///
+    /// ## Argument
+    /// * `tag`: Only includes bookmarks with this bookmark tag.
+    /// * `visibility`: Whether to list the public or the private bookmarks.
+    ///   Private bookmarks are only visible to the account holder.
+    pub fn user_bookmarks(
+        &self,
+        user_id: i32,
+        tag: Option<&str>,
+        visibility: Visibility,
+    ) -> PagedStream<'static, BookmarkedWork<IllustProfile>, Offset> {
+        self.bookmarks("illusts", user_id, tag, visibility, Offset::new(48))
+    }
+
+    /// Get the bookmarked novels of an user as a stream.
+    ///
+    /// See [`PixivClient::user_bookmarks`] for the arguments.
+    pub fn user_novel_bookmarks(
+        &self,
+        user_id: i32,
+        tag: Option<&str>,
+        visibility: Visibility,
+    ) -> PagedStream<'static, BookmarkedWork<NovelProfile>, Offset> {
+        self.bookmarks("novels", user_id, tag, visibility, Offset::new(24))
+    }
+
+    /// Get the bookmark tags of an user on illusts and mangas, with their counts.
+    pub fn user_bookmark_tags(&self, user_id: i32) -> GetRequest<'_, BookmarkTags> {
+        self.get(self.url(&format!("/ajax/user/{user_id}/illusts/bookmark/tags")))
+    }
+
+    /// Get the bookmark tags of an user on novels, with their counts.
+    pub fn user_novel_bookmark_tags(&self, user_id: i32) -> GetRequest<'_, BookmarkTags> {
+        self.get(self.url(&format!("/ajax/user/{user_id}/novels/bookmark/tags")))
+    }
+
+    fn bookmarks<T>(
+        &self,
+        kind: &'static str,
+        user_id: i32,
+        tag: Option<&str>,
+        visibility: Visibility,
+        start: Offset,
+    ) -> PagedStream<'static, BookmarkedWork<T>, Offset>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let base = self.url(&format!("/ajax/user/{user_id}/{kind}/bookmarks"));
+        let tag = tag.unwrap_or_default().to_string();
+        let client = self.clone();
+        PagedStream::new(start, move |cursor: Offset| {
+            let client = client.clone();
+            let url = Url::parse_with_params(
+                &base,
+                [
+                    ("tag", tag.as_str()),
+                    ("offset", &cursor.offset.to_string()),
+                    ("limit", &cursor.limit.to_string()),
+                    ("rest", visibility.rest()),
+                ],
+            )
+            .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"));
+            async move {
+                let UserBookmarks { works, total } = client.get::<UserBookmarks<T>>(url?).await?;
+                Ok(Page {
+                    next: cursor.next_page(works.len(), Some(total)),
+                    items: works,
+                    total: Some(total),
+                })
+            }
+        })
+    }
+}
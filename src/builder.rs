@@ -0,0 +1,142 @@
+//! Builder for [`PixivClient`].
+
+use std::sync::Arc;
+
+use reqwest::header::HeaderValue;
+use reqwest::{Client, Url};
+
+use crate::limit::{Limiter, RateLimit};
+use crate::retry::RetryPolicy;
+use crate::{ClientInner, PixivClient};
+
+/// A builder to create a [`PixivClient`] with custom settings.
+///
+/// This is useful to point the client at a mirror or a local stand-in server.
+#[derive(Debug, Clone)]
+pub struct PixivClientBuilder {
+    token: String,
+    client: Option<Client>,
+    base_url: String,
+    image_host: String,
+    referer: Option<String>,
+    retry: RetryPolicy,
+    api_limit: RateLimit,
+    image_limit: RateLimit,
+}
+
+impl PixivClientBuilder {
+    /// Creates a new builder.
+    /// ## Argument
+    /// * `token`: The session token on your web session. See the [PixivFE guide](https://pixivfe.pages.dev/obtaining-pixivfe-token/) for how to get it.
+    pub fn new(token: &str) -> Self {
+        PixivClientBuilder {
+            token: token.to_string(),
+            client: None,
+            base_url: crate::BASE_URL_HTTPS.to_string(),
+            image_host: crate::IMAGE_HOST_HTTPS.to_string(),
+            referer: None,
+            retry: RetryPolicy::never(),
+            api_limit: RateLimit::unlimited(),
+            image_limit: RateLimit::unlimited(),
+        }
+    }
+
+    /// Uses an existing [reqwest::Client].
+    pub fn client(mut self, client: &reqwest::Client) -> Self {
+        self.client = Some(client.clone());
+        self
+    }
+
+    /// Sets the base URL used for all the API requests.
+    ///
+    /// Defaults to `https://www.pixiv.net`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Sets the host serving the images, replacing `i.pximg.net`.
+    ///
+    /// Defaults to `https://i.pximg.net`.
+    pub fn image_host(mut self, image_host: impl Into<String>) -> Self {
+        self.image_host = image_host.into();
+        self
+    }
+
+    /// Sets the `Referer` header sent with every request.
+    ///
+    /// Defaults to `https://www.pixiv.net`.
+    pub fn referer(mut self, referer: impl Into<String>) -> Self {
+        self.referer = Some(referer.into());
+        self
+    }
+
+    /// Sets the policy to retry failed GET requests and image downloads.
+    ///
+    /// Defaults to [`RetryPolicy::never`]. POST requests are not retried
+    /// unless asked with [`crate::futures::PostRequest::retry`], as they may
+    /// not be safe to send twice.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Limits the requests to the Pixiv API.
+    ///
+    /// Defaults to [`RateLimit::unlimited`].
+    pub fn api_limit(mut self, limit: RateLimit) -> Self {
+        self.api_limit = limit;
+        self
+    }
+
+    /// Limits the image downloads, separately from the API requests.
+    ///
+    /// Defaults to [`RateLimit::unlimited`].
+    pub fn image_limit(mut self, limit: RateLimit) -> Self {
+        self.image_limit = limit;
+        self
+    }
+
+    /// Creates the client.
+    pub fn build(self) -> crate::Result<PixivClient> {
+        let cookie = format!("PHPSESSID={}", self.token);
+        let mut cookie = HeaderValue::from_str(&cookie)
+            .map_err(|_| crate::Error::InvalidArgument("Cookies data seems to be invaild"))?;
+        cookie.set_sensitive(true);
+        let base_url = parse_base(&self.base_url).ok_or(crate::Error::InvalidArgument(
+            "Base URL seems to be invalid",
+        ))?;
+        let image_host = parse_base(&self.image_host).ok_or(crate::Error::InvalidArgument(
+            "Image host seems to be invalid",
+        ))?;
+        self.api_limit.validate()?;
+        self.image_limit.validate()?;
+        let referer = match self.referer {
+            Some(referer) => HeaderValue::from_str(&referer)
+                .map_err(|_| crate::Error::InvalidArgument("Referer seems to be invalid"))?,
+            None => crate::REFERER_HEADER_VALUE.clone(),
+        };
+        Ok(PixivClient {
+            inner: Arc::new(ClientInner {
+                client: self.client.unwrap_or_default(),
+                cookie,
+                base_url,
+                image_host,
+                referer,
+                csrf_token: Default::default(),
+                retry: self.retry,
+                api_limiter: Limiter::new(self.api_limit),
+                image_limiter: Limiter::new(self.image_limit),
+            }),
+        })
+    }
+}
+
+/// Validates a base URL, returning it without the trailing slash.
+fn parse_base(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    if parsed.cannot_be_a_base() {
+        return None;
+    }
+    Some(url.trim_end_matches('/').to_string())
+}
@@ -0,0 +1,268 @@
+//! Comment endpoints.
+
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+use crate::paginate::{Offset, Page, PagedStream};
+use crate::{Comment, PixivClient};
+
+/// A work that can be commented.
+#[derive(Clone, Copy)]
+enum Work {
+    Illust(i32),
+    Novel(i32),
+}
+
+impl Work {
+    fn kind(self) -> &'static str {
+        match self {
+            Work::Illust(_) => "illusts",
+            Work::Novel(_) => "novels",
+        }
+    }
+
+    /// The prefix of the legacy RPC endpoints.
+    fn rpc_prefix(self) -> &'static str {
+        match self {
+            Work::Illust(_) => "",
+            Work::Novel(_) => "/novel",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Comments {
+    comments: Vec<Comment>,
+    has_next: bool,
+}
+
+#[derive(Serialize)]
+struct PostCommentForm<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    illust_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    novel_id: Option<i32>,
+    author_user_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stamp_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_id: Option<i64>,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct PostedComment {
+    #[serde_as(as = "DisplayFromStr")]
+    comment_id: i64,
+}
+
+impl PixivClient {
+    /// Get the comments on an illust as a stream, newest first.
+    ///
+    /// Replies are not included, see [`PixivClient::comment_replies`].
+    pub fn illust_comments(&self, illust_id: i32) -> PagedStream<'static, Comment, Offset> {
+        self.comments(Work::Illust(illust_id))
+    }
+
+    /// Get the replies to a comment on an illust as a stream.
+    pub fn comment_replies(&self, comment_id: i64) -> PagedStream<'static, Comment, u32> {
+        self.replies("illusts", comment_id)
+    }
+
+    /// Posts a comment on an illust, returning the ID of the comment.
+    ///
+    /// ## Argument
+    /// * `author_id`: The User ID of the author of the illust.
+    /// * `parent_id`: The ID of the comment to reply to.
+    pub async fn post_comment(
+        &self,
+        illust_id: i32,
+        author_id: i32,
+        comment: &str,
+        parent_id: Option<i64>,
+    ) -> crate::Result<i64> {
+        self.send_comment(
+            Work::Illust(illust_id),
+            author_id,
+            Some(comment),
+            None,
+            parent_id,
+        )
+        .await
+    }
+
+    /// Posts a stamp on an illust, returning the ID of the comment.
+    ///
+    /// See [`PixivClient::post_comment`] for the arguments.
+    pub async fn post_stamp(
+        &self,
+        illust_id: i32,
+        author_id: i32,
+        stamp_id: i32,
+        parent_id: Option<i64>,
+    ) -> crate::Result<i64> {
+        self.send_comment(
+            Work::Illust(illust_id),
+            author_id,
+            None,
+            Some(stamp_id),
+            parent_id,
+        )
+        .await
+    }
+
+    /// Deletes a comment on an illust.
+    pub async fn delete_comment(&self, illust_id: i32, comment_id: i64) -> crate::Result<()> {
+        self.remove_comment(Work::Illust(illust_id), comment_id)
+            .await
+    }
+
+    /// Get the comments on a novel as a stream, newest first.
+    ///
+    /// Replies are not included, see [`PixivClient::novel_comment_replies`].
+    pub fn novel_comments(&self, novel_id: i32) -> PagedStream<'static, Comment, Offset> {
+        self.comments(Work::Novel(novel_id))
+    }
+
+    /// Get the replies to a comment on a novel as a stream.
+    pub fn novel_comment_replies(&self, comment_id: i64) -> PagedStream<'static, Comment, u32> {
+        self.replies("novels", comment_id)
+    }
+
+    /// Posts a comment on a novel, returning the ID of the comment.
+    ///
+    /// See [`PixivClient::post_comment`] for the arguments.
+    pub async fn post_novel_comment(
+        &self,
+        novel_id: i32,
+        author_id: i32,
+        comment: &str,
+        parent_id: Option<i64>,
+    ) -> crate::Result<i64> {
+        self.send_comment(
+            Work::Novel(novel_id),
+            author_id,
+            Some(comment),
+            None,
+            parent_id,
+        )
+        .await
+    }
+
+    /// Posts a stamp on a novel, returning the ID of the comment.
+    ///
+    /// See [`PixivClient::post_comment`] for the arguments.
+    pub async fn post_novel_stamp(
+        &self,
+        novel_id: i32,
+        author_id: i32,
+        stamp_id: i32,
+        parent_id: Option<i64>,
+    ) -> crate::Result<i64> {
+        self.send_comment(
+            Work::Novel(novel_id),
+            author_id,
+            None,
+            Some(stamp_id),
+            parent_id,
+        )
+        .await
+    }
+
+    /// Deletes a comment on a novel.
+    pub async fn delete_novel_comment(&self, novel_id: i32, comment_id: i64) -> crate::Result<()> {
+        self.remove_comment(Work::Novel(novel_id), comment_id).await
+    }
+
+    fn comments(&self, work: Work) -> PagedStream<'static, Comment, Offset> {
+        let (key, id) = match work {
+            Work::Illust(id) => ("illust_id", id),
+            Work::Novel(id) => ("novel_id", id),
+        };
+        let base = self.url(&format!("/ajax/{}/comments/roots", work.kind()));
+        let client = self.clone();
+        PagedStream::new(Offset::new(50), move |cursor: Offset| {
+            let client = client.clone();
+            let url = format!(
+                "{base}?{key}={id}&offset={}&limit={}",
+                cursor.offset, cursor.limit
+            );
+            async move {
+                let Comments { comments, has_next } = client.get::<Comments>(url).await?;
+                Ok(Page {
+                    next: if has_next {
+                        cursor.next_page(comments.len(), None)
+                    } else {
+                        None
+                    },
+                    items: comments,
+                    total: None,
+                })
+            }
+        })
+    }
+
+    fn replies(&self, kind: &'static str, comment_id: i64) -> PagedStream<'static, Comment, u32> {
+        let base = self.url(&format!("/ajax/{kind}/comments/replies"));
+        let client = self.clone();
+        PagedStream::new(1, move |page: u32| {
+            let client = client.clone();
+            let url = format!("{base}?comment_id={comment_id}&page={page}");
+            async move {
+                let Comments { comments, has_next } = client.get::<Comments>(url).await?;
+                Ok(Page {
+                    items: comments,
+                    next: has_next.then_some(page + 1),
+                    total: None,
+                })
+            }
+        })
+    }
+
+    async fn send_comment(
+        &self,
+        work: Work,
+        author_id: i32,
+        comment: Option<&str>,
+        stamp_id: Option<i32>,
+        parent_id: Option<i64>,
+    ) -> crate::Result<i64> {
+        let (illust_id, novel_id) = match work {
+            Work::Illust(id) => (Some(id), None),
+            Work::Novel(id) => (None, Some(id)),
+        };
+        let form = PostCommentForm {
+            kind: if stamp_id.is_some() {
+                "stamp"
+            } else {
+                "comment"
+            },
+            illust_id,
+            novel_id,
+            author_user_id: author_id,
+            comment,
+            stamp_id,
+            parent_id,
+        };
+        let posted: PostedComment = self
+            .post(self.url(&format!("{}/rpc/post_comment.php", work.rpc_prefix())))
+            .form(&form)
+            .await?;
+        Ok(posted.comment_id)
+    }
+
+    async fn remove_comment(&self, work: Work, comment_id: i64) -> crate::Result<()> {
+        let (Work::Illust(id) | Work::Novel(id)) = work;
+        let _: IgnoredAny = self
+            .post(self.url(&format!("{}/rpc_delete_comment.php", work.rpc_prefix())))
+            .form(&[("i_id", id.to_string()), ("del_id", comment_id.to_string())])
+            .await?;
+        Ok(())
+    }
+}
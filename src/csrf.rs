@@ -0,0 +1,145 @@
+//! CSRF token handling for authenticated POST requests.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use reqwest::{Method, StatusCode, Url};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::check_status;
+use crate::PixivClient;
+
+static NEXT_DATA_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<script[^>]*id="__NEXT_DATA__"[^>]*>(.*?)</script>"#).unwrap()
+});
+static GLOBAL_DATA_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"id="meta-global-data"[^>]*content='([^']*)'"#).unwrap());
+
+/// The body of a POST request.
+#[derive(Debug, Clone)]
+pub(crate) enum PostBody {
+    Empty,
+    Json(Vec<u8>),
+    Form(String),
+}
+
+impl PostBody {
+    /// Serializes `body` as JSON.
+    pub(crate) fn json<B: Serialize + ?Sized>(body: &B) -> crate::Result<Self> {
+        serde_json::to_vec(body)
+            .map(PostBody::Json)
+            .map_err(|_| crate::Error::InvalidArgument("Failed to serialize the request body"))
+    }
+
+    /// Serializes `body` as an URL encoded form.
+    pub(crate) fn form<B: Serialize + ?Sized>(body: &B) -> crate::Result<Self> {
+        serde_urlencoded::to_string(body)
+            .map(PostBody::Form)
+            .map_err(|_| crate::Error::InvalidArgument("Failed to serialize the request body"))
+    }
+}
+
+/// Extracts the CSRF token from a Pixiv page, looking into the embedded
+/// `__NEXT_DATA__` and the legacy `meta-global-data` preload data.
+pub(crate) fn extract_csrf_token(html: &str) -> Option<String> {
+    let next_data = NEXT_DATA_RE.captures(html).map(|caps| caps[1].to_string());
+    let global_data = GLOBAL_DATA_RE
+        .captures(html)
+        .map(|caps| caps[1].replace("&quot;", "\"").replace("&amp;", "&"));
+    next_data
+        .into_iter()
+        .chain(global_data)
+        .filter_map(|data| serde_json::from_str::<Value>(&data).ok())
+        .find_map(|value| find_token(&value))
+}
+
+/// Searches for the `token` key, descending into nested serialized JSON.
+fn find_token(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(token)) = map.get("token") {
+                if !token.is_empty() && token.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Some(token.clone());
+                }
+            }
+            map.values().find_map(find_token)
+        }
+        Value::Array(values) => values.iter().find_map(find_token),
+        Value::String(text) if text.starts_with('{') => serde_json::from_str::<Value>(text)
+            .ok()
+            .and_then(|value| find_token(&value)),
+        _ => None,
+    }
+}
+
+impl PixivClient {
+    /// Get the CSRF token of the web session, used for POST requests.
+    ///
+    /// The token is cached in the client and refreshed when a POST request
+    /// is rejected.
+    pub async fn csrf_token(&self) -> crate::Result<String> {
+        if let Some(token) = self.inner.csrf_token.lock().unwrap().clone() {
+            return Ok(token);
+        }
+        let html = {
+            let _permit = self.inner.api_limiter.acquire().await;
+            let resp = self
+                .request(Method::GET, &self.inner.base_url)
+                .send()
+                .await?;
+            check_status(resp).await?.text().await?
+        };
+        let token = extract_csrf_token(&html).ok_or_else(|| {
+            crate::Error::Unauthorized("No CSRF token found, the session may have expired".into())
+        })?;
+        *self.inner.csrf_token.lock().unwrap() = Some(token.clone());
+        Ok(token)
+    }
+
+    fn invalidate_csrf_token(&self) {
+        *self.inner.csrf_token.lock().unwrap() = None;
+    }
+
+    /// Sends a POST request with the CSRF token, refreshing the token and
+    /// retrying once if the request is rejected with 400 or 403.
+    pub(crate) async fn send_post(
+        &self,
+        url: Url,
+        body: &PostBody,
+    ) -> crate::Result<reqwest::Response> {
+        let mut refreshed = false;
+        loop {
+            let token = self.csrf_token().await?;
+            let request = self
+                .request(Method::POST, url.clone())
+                .header("x-csrf-token", token);
+            let request = match body {
+                PostBody::Empty => request,
+                PostBody::Json(body) => request
+                    .header(
+                        reqwest::header::CONTENT_TYPE,
+                        "application/json; charset=utf-8",
+                    )
+                    .body(body.clone()),
+                PostBody::Form(body) => request
+                    .header(
+                        reqwest::header::CONTENT_TYPE,
+                        "application/x-www-form-urlencoded; charset=utf-8",
+                    )
+                    .body(body.clone()),
+            };
+            let permit = self.inner.api_limiter.acquire().await;
+            let resp = request.send().await?;
+            let status = resp.status();
+            if !refreshed && (status == StatusCode::BAD_REQUEST || status == StatusCode::FORBIDDEN)
+            {
+                drop(permit);
+                self.invalidate_csrf_token();
+                refreshed = true;
+                continue;
+            }
+            return check_status(resp).await;
+        }
+    }
+}
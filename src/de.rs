@@ -1,80 +1,308 @@
 //! Deserialize functions.
 
-use std::{collections::HashMap, fmt::Display, hash::Hash, str::FromStr};
+use std::{collections::HashMap, fmt, fmt::Display, marker::PhantomData, str::FromStr};
 
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, Error, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
 use serde::{Deserialize, Deserializer};
 
+/// Deserializes the values of an object, or the items of an array, as Pixiv
+/// sends `[]` for empty objects.
 pub(crate) fn dict_value_to_vec<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
 where
     D: Deserializer<'de>,
     T: Deserialize<'de>,
 {
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum HashMapOrVec<T> {
-        HashMap(HashMap<String, T>),
-        Vec(Vec<T>),
-    }
+    struct ValuesVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for ValuesVisitor<T> {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an object or an array")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Vec<T>, A::Error> {
+            let mut values = Vec::new();
+            while let Some((_, value)) = map.next_entry::<IgnoredAny, T>()? {
+                values.push(value);
+            }
+            Ok(values)
+        }
 
-    match HashMapOrVec::<T>::deserialize(deserializer)? {
-        HashMapOrVec::HashMap(map) => Ok(map.into_values().collect()),
-        HashMapOrVec::Vec(vec) => Ok(vec),
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+            let mut values = Vec::new();
+            while let Some(value) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(values)
+        }
     }
+
+    deserializer.deserialize_any(ValuesVisitor(PhantomData))
 }
 
+/// Deserializes the keys of an object, or the items of an array, parsing
+/// them with [`FromStr`].
 pub(crate) fn dict_key_to_vec<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
 where
     D: Deserializer<'de>,
-    T: Eq + Hash + FromStr + Deserialize<'de>,
+    T: FromStr,
     <T as FromStr>::Err: Display,
 {
-    use serde::de::IgnoredAny;
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum HashMapOrVec {
-        HashMap(HashMap<String, IgnoredAny>),
-        Vec(Vec<String>),
-    }
+    struct KeysVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for KeysVisitor<T>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an object or an array of IDs")
+        }
 
-    match HashMapOrVec::deserialize(deserializer)? {
-        HashMapOrVec::HashMap(map) => {
-            let keys = map.into_keys();
-            let res: Result<Vec<T>, <T as FromStr>::Err> =
-                keys.map(|key| <T as FromStr>::from_str(&key)).collect();
-            res.map_err(serde::de::Error::custom)
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Vec<T>, A::Error> {
+            let mut keys = Vec::new();
+            while let Some(key) = map.next_key::<String>()? {
+                map.next_value::<IgnoredAny>()?;
+                keys.push(key.parse().map_err(A::Error::custom)?);
+            }
+            Ok(keys)
         }
-        HashMapOrVec::Vec(set) => {
-            let keys = set.into_iter();
-            let res: Result<Vec<T>, <T as FromStr>::Err> =
-                keys.map(|key| <T as FromStr>::from_str(&key)).collect();
-            res.map_err(serde::de::Error::custom)
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+            let mut keys = Vec::new();
+            while let Some(key) = seq.next_element::<String>()? {
+                keys.push(key.parse().map_err(A::Error::custom)?);
+            }
+            Ok(keys)
         }
     }
+
+    deserializer.deserialize_any(KeysVisitor(PhantomData))
 }
 
-pub(crate) fn deserialize_err_is_none<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+/// Deserializes a list of works, skipping the ad containers mixed in.
+///
+/// The items are deserialized in place, so errors keep the path to the
+/// failing field.
+pub(crate) fn skip_ad_containers<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
 where
     D: Deserializer<'de>,
     T: Deserialize<'de>,
 {
-    Ok(T::deserialize(deserializer).ok())
+    struct ItemsVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for ItemsVisitor<T> {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a list of works")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+            let mut items = Vec::new();
+            while let Some(item) = seq.next_element_seed(ItemOrAd(PhantomData))? {
+                items.extend(item);
+            }
+            Ok(items)
+        }
+    }
+
+    deserializer.deserialize_seq(ItemsVisitor(PhantomData))
+}
+
+/// An item of a list of works, `None` if it is an ad container.
+struct ItemOrAd<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for ItemOrAd<T> {
+    type Value = Option<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Option<T>, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for ItemOrAd<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a work or an ad container")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Option<T>, A::Error> {
+        // Ad containers are `{"isAdContainer":true}`, so the first key tells them apart.
+        let key = map.next_key::<String>()?;
+        let value = match key.as_deref() {
+            Some("isAdContainer") => {
+                let is_ad_container: bool = map.next_value()?;
+                if is_ad_container {
+                    while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+                    return Ok(None);
+                }
+                Some(is_ad_container)
+            }
+            _ => None,
+        };
+        T::deserialize(MapAccessDeserializer::new(Replay { key, value, map })).map(Some)
+    }
+}
+
+/// A [`MapAccess`] yielding an entry already read before the rest of `map`.
+struct Replay<A> {
+    key: Option<String>,
+    value: Option<bool>,
+    map: A,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Replay<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        match self.key.take() {
+            Some(key) => seed.deserialize(key.into_deserializer()).map(Some),
+            None => self.map.next_key_seed(seed),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value.into_deserializer()),
+            None => self.map.next_value_seed(seed),
+        }
+    }
+}
+
+/// Deserializes a buffered JSON value, naming the failing field inside it
+/// in the error, as the path of the outer deserializer stops at the value.
+pub(crate) fn from_value<T, E>(value: serde_json::Value) -> Result<T, E>
+where
+    T: DeserializeOwned,
+    E: serde::de::Error,
+{
+    serde_path_to_error::deserialize(value).map_err(|err| {
+        let path = err.path().to_string();
+        E::custom(format_args!("at `{path}`: {}", err.into_inner()))
+    })
 }
 
+/// Deserializes `false` or `null` as `None`.
 pub(crate) fn false_is_none<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
 where
     D: Deserializer<'de>,
     T: Deserialize<'de>,
 {
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum BoolOrT<T> {
-        #[allow(dead_code)]
-        Bool(bool),
-        T(T),
+    struct FalseVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for FalseVisitor<T> {
+        type Value = Option<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("false or a value")
+        }
+
+        fn visit_bool<E: Error>(self, value: bool) -> Result<Option<T>, E> {
+            if value {
+                T::deserialize(value.into_deserializer()).map(Some)
+            } else {
+                Ok(None)
+            }
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<Option<T>, E> {
+            Ok(None)
+        }
+
+        fn visit_i64<E: Error>(self, value: i64) -> Result<Option<T>, E> {
+            T::deserialize(value.into_deserializer()).map(Some)
+        }
+
+        fn visit_u64<E: Error>(self, value: u64) -> Result<Option<T>, E> {
+            T::deserialize(value.into_deserializer()).map(Some)
+        }
+
+        fn visit_f64<E: Error>(self, value: f64) -> Result<Option<T>, E> {
+            T::deserialize(value.into_deserializer()).map(Some)
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<Option<T>, E> {
+            T::deserialize(value.into_deserializer()).map(Some)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Option<T>, A::Error> {
+            T::deserialize(MapAccessDeserializer::new(map)).map(Some)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Option<T>, A::Error> {
+            T::deserialize(SeqAccessDeserializer::new(seq)).map(Some)
+        }
     }
 
-    match BoolOrT::<T>::deserialize(deserializer)? {
-        BoolOrT::Bool(_) => Ok(None),
-        BoolOrT::T(val) => Ok(Some(val)),
+    deserializer.deserialize_any(FalseVisitor(PhantomData))
+}
+
+/// Deserializes an object, or `[]` or `null` as `None`, as Pixiv sends `[]`
+/// for missing objects.
+pub(crate) fn empty_array_is_none<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    struct ObjectVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for ObjectVisitor<T> {
+        type Value = Option<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an object or an empty array")
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<Option<T>, E> {
+            Ok(None)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Option<T>, A::Error> {
+            match seq.next_element::<IgnoredAny>()? {
+                Some(_) => Err(A::Error::invalid_length(1, &self)),
+                None => Ok(None),
+            }
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Option<T>, A::Error> {
+            T::deserialize(MapAccessDeserializer::new(map)).map(Some)
+        }
     }
+
+    deserializer.deserialize_any(ObjectVisitor(PhantomData))
+}
+
+/// Drops empty translations, as Pixiv sends every language for each tag.
+pub(crate) fn tag_translations<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, HashMap<String, String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let translations: Option<HashMap<String, HashMap<String, Option<String>>>> =
+        empty_array_is_none(deserializer)?;
+    Ok(translations
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(tag, translation)| {
+            let translation = translation
+                .into_iter()
+                .filter_map(|(lang, text)| Some((lang, text?)))
+                .filter(|(_, text)| !text.is_empty())
+                .collect();
+            (tag, translation)
+        })
+        .collect())
 }
@@ -0,0 +1,199 @@
+//! Image downloads.
+
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_util::{Stream, TryStreamExt};
+use reqwest::{StatusCode, Url};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::error::check_status;
+use crate::limit::Permit;
+use crate::PixivClient;
+
+/// A [`Stream`] of the bytes of an image being downloaded.
+pub struct ImageDownload {
+    offset: u64,
+    content_length: Option<u64>,
+    stream: Pin<Box<dyn Stream<Item = crate::Result<Bytes>> + Send>>,
+    _permit: Permit,
+}
+
+impl ImageDownload {
+    /// Returns the offset in the image the stream starts at.
+    ///
+    /// This is `0` if the server ignored the requested range.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the length of the bytes in the stream, if known.
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    /// Returns the length of the whole image, if known.
+    pub fn total_length(&self) -> Option<u64> {
+        self.content_length.map(|length| self.offset + length)
+    }
+}
+
+impl Stream for ImageDownload {
+    type Item = crate::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+/// The progress of a download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// How many bytes of the image have been downloaded, including the
+    /// bytes downloaded before resuming.
+    pub downloaded: u64,
+    /// The length of the whole image, if known.
+    pub total: Option<u64>,
+}
+
+impl PixivClient {
+    /// Downloads an image from `i.pximg.net` (or the configured image host).
+    pub async fn download_image(&self, url: &str) -> crate::Result<ImageDownload> {
+        self.download_image_from(url, 0).await
+    }
+
+    /// Downloads an image starting at `offset`, to resume a partial download.
+    ///
+    /// Check [`ImageDownload::offset`] as the server may send the whole image
+    /// instead.
+    ///
+    /// Failing to start the download is retried with the policy of the
+    /// client, but the returned stream is not.
+    pub async fn download_image_from(
+        &self,
+        url: &str,
+        offset: u64,
+    ) -> crate::Result<ImageDownload> {
+        let url = self.image_url(url)?;
+        self.inner
+            .retry
+            .run(|| self.start_download(url.clone(), offset))
+            .await
+    }
+
+    async fn start_download(&self, url: Url, offset: u64) -> crate::Result<ImageDownload> {
+        let permit = self.inner.image_limiter.acquire().await;
+        let mut request = self
+            .inner
+            .client
+            .get(url)
+            .header(reqwest::header::REFERER, self.inner.referer.clone())
+            .header(
+                reqwest::header::USER_AGENT,
+                crate::USER_AGENT_HEADER_VALUE.clone(),
+            );
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+        }
+        let resp = request.send().await?;
+        if offset > 0 && resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The range starts at the end of the image, so it is already complete.
+            if complete_length(&resp) == Some(offset) {
+                return Ok(ImageDownload {
+                    offset,
+                    content_length: Some(0),
+                    stream: Box::pin(futures_util::stream::empty()),
+                    _permit: permit,
+                });
+            }
+        }
+        let resp = check_status(resp).await?;
+        let offset = if resp.status() == StatusCode::PARTIAL_CONTENT {
+            offset
+        } else {
+            0
+        };
+        Ok(ImageDownload {
+            offset,
+            content_length: resp.content_length(),
+            stream: Box::pin(resp.bytes_stream().map_err(crate::Error::from)),
+            _permit: permit,
+        })
+    }
+
+    /// Downloads an image into `writer`, calling `progress` after each
+    /// chunk is written.
+    ///
+    /// Returns how many bytes have been written.
+    pub async fn download_image_to<W: AsyncWrite + Unpin>(
+        &self,
+        url: &str,
+        writer: &mut W,
+        progress: impl FnMut(Progress),
+    ) -> crate::Result<u64> {
+        let download = self.download_image(url).await?;
+        write_download(download, writer, progress).await
+    }
+
+    /// Downloads an image into a file, calling `progress` after each chunk
+    /// is written.
+    ///
+    /// If the file already exists, the download resumes from its end.
+    /// Returns the length of the file.
+    pub async fn download_image_to_path(
+        &self,
+        url: &str,
+        path: impl AsRef<Path>,
+        progress: impl FnMut(Progress),
+    ) -> crate::Result<u64> {
+        let path = path.as_ref();
+        let existing = match tokio::fs::metadata(path).await {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(err) => return Err(err.into()),
+        };
+        let download = self.download_image_from(url, existing).await?;
+        let mut options = tokio::fs::OpenOptions::new();
+        if download.offset() > 0 {
+            options.append(true);
+        } else {
+            options.write(true).create(true).truncate(true);
+        }
+        let mut file = options.open(path).await?;
+        let offset = download.offset();
+        let written = write_download(download, &mut file, progress).await?;
+        Ok(offset + written)
+    }
+}
+
+async fn write_download<W: AsyncWrite + Unpin>(
+    mut download: ImageDownload,
+    writer: &mut W,
+    mut progress: impl FnMut(Progress),
+) -> crate::Result<u64> {
+    let total = download.total_length();
+    let mut written = 0;
+    while let Some(chunk) = download.try_next().await? {
+        writer.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+        progress(Progress {
+            downloaded: download.offset + written,
+            total,
+        });
+    }
+    writer.flush().await?;
+    Ok(written)
+}
+
+/// Reads the complete length from a `Content-Range: bytes */length` header.
+fn complete_length(resp: &reqwest::Response) -> Option<u64> {
+    resp.headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes */")?
+        .parse()
+        .ok()
+}
@@ -1,17 +1,211 @@
 //! Error types.
+use std::time::{Duration, SystemTime};
+
+use reqwest::{header::HeaderMap, StatusCode};
+use serde::de::DeserializeOwned;
 use thiserror::Error;
 
+use crate::types::{WrappedBody, WrappedResponse};
+
 /// An error that can occur.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
-    /// The error returned when the HTTP status is 200 but Pixiv API specified
-    /// that an error occured.
-    #[error("Pixiv API Error: {0}")]
-    PixivError(String),
+    /// The work or the user does not exist or has been deleted.
+    #[error("Not Found: {0}")]
+    NotFound(String),
+    /// The session token is invalid or has expired, or the request needs
+    /// a logged in session.
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+    /// The content is restricted for the account holder, e.g. R-18 works
+    /// with R-18 content disabled in the settings.
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    /// Pixiv asked to slow down.
+    #[error("Rate Limited")]
+    RateLimited {
+        /// How long to wait before retrying, if Pixiv said so.
+        retry_after: Option<Duration>,
+    },
+    /// The response could not be decoded.
+    #[error("Deserialize Error at `{path}`: {message}")]
+    Deserialize {
+        /// The path of the value that failed to decode, e.g. `body.tags[0]`.
+        path: String,
+        /// The error message from the decoder.
+        message: String,
+        /// The part of the raw response around the error.
+        snippet: String,
+        /// The raw response, to include in bug reports.
+        raw: String,
+    },
+    /// An argument or a setting is invalid.
+    #[error("Invalid Argument: {0}")]
+    InvalidArgument(&'static str),
+    /// Pixiv reported an error not covered by the other variants.
+    #[error("Pixiv API Error: {message}")]
+    PixivError {
+        /// The HTTP status, if it is not a success.
+        status: Option<StatusCode>,
+        /// The message from Pixiv, may be empty.
+        message: String,
+    },
     /// HTTP error.
     #[error("HTTP Error")]
     HttpError(#[from] reqwest::Error),
-    /// Other error.
+    /// IO error.
+    #[error("IO Error")]
+    IoError(#[from] std::io::Error),
+    /// Error reading an ugoira archive.
+    #[error("Zip Error")]
+    ZipError(#[from] zip::result::ZipError),
+    /// Error encoding an ugoira to an animated image.
+    #[error("Encode Error: {0}")]
+    EncodeError(String),
+    /// Pixiv rejected an action.
+    #[error("Action rejected: {0}")]
+    Rejected(Rejection),
+}
+
+impl Error {
+    /// Returns whether the request may succeed if sent again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimited { .. } => true,
+            Error::PixivError {
+                status: Some(status),
+                ..
+            } => status.is_server_error(),
+            Error::HttpError(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || (err.is_request() && !err.is_builder())
+                    || err.status().is_some_and(|status| {
+                        status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns how long Pixiv asked to wait before retrying, if it did.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Creates an error from the message of an API response with the
+    /// `error` flag set.
+    pub(crate) fn from_message(status: Option<StatusCode>, message: String) -> Self {
+        let lowercase = message.to_lowercase();
+        let matches = |keywords: &[&str]| {
+            keywords
+                .iter()
+                .any(|keyword| lowercase.contains(keyword) || message.contains(keyword))
+        };
+        if matches(&["too many", "rate limit", "しばらく時間を"]) {
+            Error::RateLimited { retry_after: None }
+        } else if matches(&["not found", "not exist", "deleted", "削除", "存在しない"]) {
+            Error::NotFound(message)
+        } else if matches(&["login", "log in", "ログイン"]) {
+            Error::Unauthorized(message)
+        } else if matches(&["restricted", "permission", "制限", "閲覧できません"]) {
+            Error::Forbidden(message)
+        } else {
+            Error::PixivError { status, message }
+        }
+    }
+}
+
+/// The reason Pixiv rejected an action.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Rejection {
+    /// The account holder follows too many users.
+    #[error("Following limit reached")]
+    FollowLimitReached,
+    /// The account holder blocks too many users.
+    #[error("Blocking limit reached")]
+    BlockLimitReached,
+    /// Another reason, with the message from Pixiv.
     #[error("{0}")]
-    Other(&'static str),
+    Other(String),
+}
+
+/// Returns the response if its status is a success, or the error it reports.
+pub(crate) async fn check_status(resp: reqwest::Response) -> crate::Result<reqwest::Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let retry_after = retry_after(resp.headers());
+    let body = resp.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| Some(value.get("message")?.as_str()?.to_string()))
+        .unwrap_or_default();
+    Err(match status {
+        StatusCode::UNAUTHORIZED => Error::Unauthorized(message),
+        StatusCode::FORBIDDEN => Error::Forbidden(message),
+        StatusCode::NOT_FOUND => Error::NotFound(message),
+        StatusCode::TOO_MANY_REQUESTS => Error::RateLimited { retry_after },
+        _ => match Error::from_message(Some(status), message) {
+            Error::RateLimited { .. } => Error::RateLimited { retry_after },
+            err => err,
+        },
+    })
+}
+
+/// Decodes the body of an AJAX API response, or returns the error it reports.
+pub(crate) fn decode_wrapped<T: DeserializeOwned>(body: &str) -> crate::Result<T> {
+    let envelope: WrappedResponse = decode_json(body)?;
+    if envelope.error {
+        return Err(Error::from_message(None, envelope.message));
+    }
+    Ok(decode_json::<WrappedBody<T>>(body)?.body)
+}
+
+/// Decodes a JSON response body, keeping the path to the failing value.
+pub(crate) fn decode_json<T: DeserializeOwned>(body: &str) -> crate::Result<T> {
+    let deserializer = &mut serde_json::Deserializer::from_str(body);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        let path = err.path().to_string();
+        let inner = err.into_inner();
+        Error::Deserialize {
+            path,
+            snippet: snippet(body, inner.line(), inner.column()),
+            message: inner.to_string(),
+            raw: body.to_string(),
+        }
+    })
+}
+
+/// Returns up to 80 characters on both sides of a position in `body`.
+fn snippet(body: &str, line: usize, column: usize) -> String {
+    let Some(line) = body.lines().nth(line.saturating_sub(1)) else {
+        return String::new();
+    };
+    let mut at = column.min(line.len());
+    while !line.is_char_boundary(at) {
+        at -= 1;
+    }
+    let before: String = line[..at].chars().rev().take(80).collect();
+    let after: String = line[at..].chars().take(80).collect();
+    before.chars().rev().chain(after.chars()).collect()
+}
+
+/// Parses the `Retry-After` header, in seconds or as an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    if let Ok(seconds) = value.trim().parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
 }
@@ -0,0 +1,316 @@
+//! Follow endpoints.
+
+use std::collections::HashMap;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::csrf::PostBody;
+use crate::error::{decode_json, Rejection};
+use crate::paginate::{Offset, Page, PagedStream};
+use crate::{
+    FollowLatestMode, IllustProfile, LatestWork, NovelProfile, PixivClient, UserPreview,
+    UserProfile, Visibility,
+};
+
+#[derive(Deserialize)]
+struct UserPreviews {
+    users: Vec<UserPreview>,
+    total: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FollowLatest {
+    page: FollowLatestPage,
+    thumbnails: Thumbnails,
+    #[serde(default)]
+    users: Vec<UserProfile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FollowLatestPage {
+    ids: Vec<i32>,
+    is_last_page: bool,
+}
+
+#[derive(Deserialize)]
+struct Thumbnails {
+    #[serde(default)]
+    illust: Vec<IllustProfile>,
+    #[serde(default)]
+    novel: Vec<NovelProfile>,
+}
+
+/// A work listed in the `thumbnails` side table.
+trait Thumbnail: Sized {
+    fn from_thumbnails(thumbnails: Thumbnails) -> Vec<Self>;
+    fn id(&self) -> i32;
+    fn user_id(&self) -> i32;
+}
+
+impl Thumbnail for IllustProfile {
+    fn from_thumbnails(thumbnails: Thumbnails) -> Vec<Self> {
+        thumbnails.illust
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn user_id(&self) -> i32 {
+        self.user_id
+    }
+}
+
+impl Thumbnail for NovelProfile {
+    fn from_thumbnails(thumbnails: Thumbnails) -> Vec<Self> {
+        thumbnails.novel
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn user_id(&self) -> i32 {
+        self.user_id
+    }
+}
+
+#[derive(Deserialize)]
+struct ActionResponse {
+    // `/rpc_group_setting.php` answers with the updated entry, without the flag.
+    #[serde(default)]
+    error: bool,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Serialize)]
+struct BlockBody {
+    user_id: String,
+    action: &'static str,
+}
+
+impl PixivClient {
+    /// Follows an user.
+    ///
+    /// ## Argument
+    /// * `restrict`: Whether the follow is public or private.
+    pub async fn follow_user(&self, user_id: i32, restrict: Visibility) -> crate::Result<()> {
+        let form = [
+            ("mode", "add".to_string()),
+            ("type", "user".to_string()),
+            ("user_id", user_id.to_string()),
+            ("tag", String::new()),
+            ("restrict", restrict.restrict().to_string()),
+            ("format", "json".to_string()),
+        ];
+        self.user_action(
+            "/bookmark_add.php",
+            PostBody::form(&form)?,
+            Some(Rejection::FollowLimitReached),
+        )
+        .await
+    }
+
+    /// Unfollows an user.
+    pub async fn unfollow_user(&self, user_id: i32) -> crate::Result<()> {
+        let form = [
+            ("mode", "del".to_string()),
+            ("type", "bookuser".to_string()),
+            ("id", user_id.to_string()),
+        ];
+        self.user_action("/rpc_group_setting.php", PostBody::form(&form)?, None)
+            .await
+    }
+
+    /// Blocks an user.
+    pub async fn block_user(&self, user_id: i32) -> crate::Result<()> {
+        self.block(user_id, "block").await
+    }
+
+    /// Unblocks an user.
+    pub async fn unblock_user(&self, user_id: i32) -> crate::Result<()> {
+        self.block(user_id, "unblock").await
+    }
+
+    async fn block(&self, user_id: i32, action: &'static str) -> crate::Result<()> {
+        let body = BlockBody {
+            user_id: user_id.to_string(),
+            action,
+        };
+        self.user_action(
+            "/ajax/block/save",
+            PostBody::json(&body)?,
+            Some(Rejection::BlockLimitReached),
+        )
+        .await
+    }
+
+    /// Sends an user action, turning an error message into a [`Rejection`].
+    ///
+    /// `limit` is the rejection reported when Pixiv mentions a limit, if the
+    /// action has one.
+    async fn user_action(
+        &self,
+        path: &str,
+        body: PostBody,
+        limit: Option<Rejection>,
+    ) -> crate::Result<()> {
+        let url = Url::parse(&self.url(path))
+            .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"))?;
+        let text = self.send_post(url, &body).await?.text().await?;
+        // Some of these endpoints answer with an empty body or `[]` on success.
+        if matches!(text.trim(), "" | "[]") {
+            return Ok(());
+        }
+        let response: ActionResponse = decode_json(&text)?;
+        if response.error {
+            return Err(crate::Error::Rejected(rejection(response.message, limit)));
+        }
+        Ok(())
+    }
+
+    /// Get the latest illusts and mangas of the users followed by the
+    /// account holder as a stream, newest first.
+    pub fn follow_latest(
+        &self,
+        mode: FollowLatestMode,
+    ) -> PagedStream<'static, LatestWork<IllustProfile>, u32> {
+        self.follow_latest_works("illust", mode)
+    }
+
+    /// Get the latest novels of the users followed by the account holder as
+    /// a stream, newest first.
+    pub fn follow_latest_novels(
+        &self,
+        mode: FollowLatestMode,
+    ) -> PagedStream<'static, LatestWork<NovelProfile>, u32> {
+        self.follow_latest_works("novel", mode)
+    }
+
+    fn follow_latest_works<T>(
+        &self,
+        kind: &'static str,
+        mode: FollowLatestMode,
+    ) -> PagedStream<'static, LatestWork<T>, u32>
+    where
+        T: Thumbnail + Send + 'static,
+    {
+        let mode = match mode {
+            FollowLatestMode::All => "all",
+            FollowLatestMode::R18 => "r18",
+        };
+        let base = self.url(&format!("/ajax/follow_latest/{kind}"));
+        let client = self.clone();
+        PagedStream::new(1, move |page: u32| {
+            let client = client.clone();
+            let url = format!("{base}?p={page}&mode={mode}");
+            async move {
+                let FollowLatest {
+                    page: ids,
+                    thumbnails,
+                    users,
+                } = client.get::<FollowLatest>(url).await?;
+                let mut works: HashMap<i32, T> = T::from_thumbnails(thumbnails)
+                    .into_iter()
+                    .map(|work| (work.id(), work))
+                    .collect();
+                let users: HashMap<i32, UserProfile> =
+                    users.into_iter().map(|user| (user.user_id, user)).collect();
+                // `ids` holds the feed order, works missing from the side table are skipped.
+                let items = ids
+                    .ids
+                    .iter()
+                    .filter_map(|id| works.remove(id))
+                    .map(|work| LatestWork {
+                        user: users.get(&work.user_id()).cloned(),
+                        work,
+                    })
+                    .collect();
+                Ok(Page {
+                    items,
+                    next: (!ids.is_last_page).then_some(page + 1),
+                    total: None,
+                })
+            }
+        })
+    }
+
+    /// Get the users followed by an user as a stream.
+    ///
+    /// ## Argument
+    /// * `rest`: Whether to list the public or the private follows.
+    ///   Private follows are only visible to the account holder.
+    pub fn user_following(
+        &self,
+        user_id: i32,
+        rest: Visibility,
+    ) -> PagedStream<'static, UserPreview, Offset> {
+        self.user_previews(
+            &format!("/ajax/user/{user_id}/following"),
+            Some(rest.rest()),
+        )
+    }
+
+    /// Get the followers of an user as a stream.
+    pub fn user_followers(&self, user_id: i32) -> PagedStream<'static, UserPreview, Offset> {
+        self.user_previews(&format!("/ajax/user/{user_id}/followers"), None)
+    }
+
+    /// Get the mypixiv users of an user as a stream.
+    pub fn user_mypixiv(&self, user_id: i32) -> PagedStream<'static, UserPreview, Offset> {
+        self.user_previews(&format!("/ajax/user/{user_id}/mypixiv"), None)
+    }
+
+    fn user_previews(
+        &self,
+        path: &str,
+        rest: Option<&'static str>,
+    ) -> PagedStream<'static, UserPreview, Offset> {
+        let base = self.url(path);
+        let client = self.clone();
+        PagedStream::new(Offset::new(24), move |cursor: Offset| {
+            let client = client.clone();
+            let url = Url::parse(&base)
+                .map(|mut url| {
+                    {
+                        let mut query = url.query_pairs_mut();
+                        query.append_pair("offset", &cursor.offset.to_string());
+                        query.append_pair("limit", &cursor.limit.to_string());
+                        if let Some(rest) = rest {
+                            query.append_pair("rest", rest);
+                        }
+                    }
+                    url
+                })
+                .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"));
+            async move {
+                let UserPreviews { users, total } = client.get::<UserPreviews>(url?).await?;
+                Ok(Page {
+                    next: cursor.next_page(users.len(), Some(total)),
+                    items: users,
+                    total: Some(total),
+                })
+            }
+        })
+    }
+}
+
+/// Tells the rejection from the error message, as Pixiv only reports a
+/// localized message.
+fn rejection(message: String, limit: Option<Rejection>) -> Rejection {
+    let lowercase = message.to_lowercase();
+    match limit {
+        Some(limit)
+            if lowercase.contains("limit")
+                || lowercase.contains("maximum")
+                || message.contains("上限") =>
+        {
+            limit
+        }
+        _ => Rejection::Other(message),
+    }
+}
@@ -1,25 +1,45 @@
 //! Named futures.
 
-use reqwest::header::HeaderValue;
 use reqwest::Url;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::borrow::Cow;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
 
-use crate::types::WrappedResponse;
+use crate::csrf::PostBody;
+use crate::error::decode_wrapped;
+use crate::retry::RetryPolicy;
 
 /// `IntoFuture` returned by [`crate::PixivClient::get`].
 pub struct GetRequest<'a, T: DeserializeOwned> {
-    pub(crate) client: &'a reqwest::Client,
-    pub(crate) cookie: HeaderValue,
+    pub(crate) client: Cow<'a, crate::PixivClient>,
     pub(crate) url: Result<Url, reqwest::Error>,
+    pub(crate) retry: Option<RetryPolicy>,
 
     pub(crate) _type: PhantomData<T>,
 }
 
 impl<T: DeserializeOwned> GetRequest<'_, T> {
+    /// Takes a handle to the client, so the request can outlive the borrow
+    /// of the client, e.g. to be moved into `tokio::spawn`.
+    pub fn into_owned(self) -> GetRequest<'static, T> {
+        GetRequest {
+            client: Cow::Owned(self.client.into_owned()),
+            url: self.url,
+            retry: self.retry,
+            _type: PhantomData,
+        }
+    }
+
+    /// Uses `retry` for this request instead of the policy of the client.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
     /// Specify the language for this request.
     pub fn with_lang(mut self, lang: &str) -> Self {
         match self.url {
@@ -42,23 +62,74 @@ impl<'a, T: DeserializeOwned> IntoFuture for GetRequest<'a, T> {
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
+            let retry = self.retry.unwrap_or(*self.client.retry_policy());
             self.client
-                .get(self.url?)
-                .header(reqwest::header::COOKIE, self.cookie)
-                .header(
-                    reqwest::header::REFERER,
-                    crate::REFERER_HEADER_VALUE.clone(),
-                )
-                .header(
-                    reqwest::header::USER_AGENT,
-                    crate::USER_AGENT_HEADER_VALUE.clone(),
-                )
-                .send()
-                .await?
-                .error_for_status()?
-                .json::<WrappedResponse<T>>()
-                .await?
-                .into()
+                .get_json(self.url?, &retry, decode_wrapped)
+                .await
+        })
+    }
+}
+
+/// `IntoFuture` returned by [`crate::PixivClient::post`].
+pub struct PostRequest<'a, T: DeserializeOwned> {
+    pub(crate) client: Cow<'a, crate::PixivClient>,
+    pub(crate) url: Result<Url, reqwest::Error>,
+    pub(crate) body: crate::Result<PostBody>,
+    pub(crate) retry: Option<RetryPolicy>,
+
+    pub(crate) _type: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> PostRequest<'_, T> {
+    /// Takes a handle to the client, so the request can outlive the borrow
+    /// of the client, e.g. to be moved into `tokio::spawn`.
+    pub fn into_owned(self) -> PostRequest<'static, T> {
+        PostRequest {
+            client: Cow::Owned(self.client.into_owned()),
+            url: self.url,
+            body: self.body,
+            retry: self.retry,
+            _type: PhantomData,
+        }
+    }
+
+    /// Retries this request with `retry`.
+    ///
+    /// POST requests are not retried by default, only use this if the
+    /// request is safe to send twice.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sends `body` serialized as JSON.
+    pub fn json<B: Serialize + ?Sized>(mut self, body: &B) -> Self {
+        self.body = PostBody::json(body);
+        self
+    }
+
+    /// Sends `body` serialized as an URL encoded form.
+    pub fn form<B: Serialize + ?Sized>(mut self, body: &B) -> Self {
+        self.body = PostBody::form(body);
+        self
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a, T: DeserializeOwned> IntoFuture for PostRequest<'a, T> {
+    type Output = crate::Result<T>;
+    type IntoFuture = Pin<Box<dyn Future<Output = crate::Result<T>> + Send + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let (url, body) = (self.url?, self.body?);
+            let retry = self.retry.unwrap_or_else(RetryPolicy::never);
+            retry
+                .run(|| async {
+                    let resp = self.client.send_post(url.clone(), &body).await?;
+                    decode_wrapped(&resp.text().await?)
+                })
+                .await
         })
     }
 }
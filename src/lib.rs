@@ -3,24 +3,46 @@
 //! ## Features
 //!
 //! * `rustls-tls`: Enables the `rustls-tls` feature of reqwest.
+//! * `ugoira-encode`: Enables encoding ugoira frames to animated images in `ugoira::encode`.
 #![warn(missing_docs)]
 #![warn(rustdoc::missing_crate_level_docs)]
+mod bookmark;
+mod builder;
+mod comment;
+mod csrf;
 mod de;
+pub mod download;
 pub mod error;
+mod follow;
 pub mod futures;
+mod like;
+pub mod limit;
+pub mod novel;
+pub mod paginate;
+pub mod recommend;
+pub mod retry;
+pub mod search;
+mod series;
+mod tag;
 pub mod types;
+pub mod ugoira;
+mod works;
 
+use std::borrow::Cow;
 use std::str::FromStr;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, Mutex};
 
-use async_stream::try_stream;
-use futures::GetRequest;
-use futures_util::Stream;
-use regex::Regex;
-use reqwest::{header::HeaderValue, Client};
+use csrf::PostBody;
+use error::{check_status, decode_json};
+use futures::{GetRequest, PostRequest};
+use limit::Limiter;
+use paginate::{Offset, Page, PagedStream};
+use reqwest::{header::HeaderValue, Client, Method, RequestBuilder, Url};
+use retry::RetryPolicy;
 use serde::de::DeserializeOwned;
 use std::marker::PhantomData;
 
+pub use crate::builder::PixivClientBuilder;
 pub use crate::error::Error;
 pub use crate::types::*;
 
@@ -28,12 +50,28 @@ pub use crate::types::*;
 pub type Result<T> = std::result::Result<T, crate::Error>;
 
 /// The client to send Pixiv API requests.
+///
+/// The client is cheap to clone: clones share the connection pool, the CSRF
+/// token and the rate limits, so a clone can be moved into another task.
+#[derive(Clone)]
 pub struct PixivClient {
+    inner: Arc<ClientInner>,
+}
+
+struct ClientInner {
     client: Client,
     cookie: HeaderValue,
+    base_url: String,
+    image_host: String,
+    referer: HeaderValue,
+    csrf_token: Mutex<Option<String>>,
+    retry: RetryPolicy,
+    api_limiter: Limiter,
+    image_limiter: Limiter,
 }
 
 static BASE_URL_HTTPS: &str = "https://www.pixiv.net";
+static IMAGE_HOST_HTTPS: &str = "https://i.pximg.net";
 static USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/114.0.0.0 Safari/537.36";
 
 pub(crate) static USER_AGENT_HEADER_VALUE: LazyLock<HeaderValue> =
@@ -46,48 +84,119 @@ impl PixivClient {
     /// ## Argument
     /// * `token`: The session token on your web session. See the [PixivFE guide](https://pixivfe.pages.dev/obtaining-pixivfe-token/) for how to get it.
     pub async fn new(token: &str) -> Result<Self> {
-        let cookie = format!("PHPSESSID={token}");
-        let mut cookie = HeaderValue::from_str(&cookie)
-            .map_err(|_| crate::Error::Other("Cookies data seems to be invaild"))?;
-        cookie.set_sensitive(true);
-        let client = Client::new();
-        Ok(PixivClient { client, cookie })
+        PixivClientBuilder::new(token).build()
     }
 
     /// Creates a new client using an existing [reqwest::Client].
     /// ## Argument
     /// * `token`: The session token on your web session. See the [PixivFE guide](https://pixivfe.pages.dev/obtaining-pixivfe-token/) for how to get it.
     pub async fn from_client(token: &str, client: &reqwest::Client) -> Result<Self> {
-        let cookie = format!("PHPSESSID={token}");
-        let mut cookie = HeaderValue::from_str(&cookie)
-            .map_err(|_| crate::Error::Other("Cookies data seems to be invaild"))?;
-        cookie.set_sensitive(true);
-        let client = client.clone();
-        Ok(PixivClient { client, cookie })
+        PixivClientBuilder::new(token).client(client).build()
+    }
+
+    /// Creates a [`PixivClientBuilder`] to configure the client.
+    pub fn builder(token: &str) -> PixivClientBuilder {
+        PixivClientBuilder::new(token)
     }
 
     /// Performs a GET request with Pixiv Web credentials.
     pub fn get<T: DeserializeOwned>(&self, url: impl reqwest::IntoUrl) -> GetRequest<'_, T> {
         let url = url.into_url();
         GetRequest {
-            client: &self.client,
-            cookie: self.cookie.clone(),
+            client: Cow::Borrowed(self),
+            url,
+            retry: None,
+            _type: PhantomData,
+        }
+    }
+
+    /// Performs a POST request with Pixiv Web credentials and the CSRF token.
+    ///
+    /// Set the body with [`PostRequest::json`] or [`PostRequest::form`].
+    pub fn post<T: DeserializeOwned>(&self, url: impl reqwest::IntoUrl) -> PostRequest<'_, T> {
+        let url = url.into_url();
+        PostRequest {
+            client: Cow::Borrowed(self),
             url,
+            body: Ok(PostBody::Empty),
+            retry: None,
             _type: PhantomData,
         }
     }
 
+    /// Sends a GET request and decodes the response body with `decode`,
+    /// retrying with `retry`, including on errors reported in the body.
+    pub(crate) async fn get_json<T>(
+        &self,
+        url: Url,
+        retry: &RetryPolicy,
+        decode: fn(&str) -> Result<T>,
+    ) -> Result<T> {
+        retry
+            .run(|| async {
+                let _permit = self.inner.api_limiter.acquire().await;
+                let resp = self.request(Method::GET, url.clone()).send().await?;
+                decode(&check_status(resp).await?.text().await?)
+            })
+            .await
+    }
+
+    /// Returns the retry policy of the client.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.inner.retry
+    }
+
+    /// Creates a request with Pixiv Web credentials and headers set.
+    pub(crate) fn request(&self, method: Method, url: impl reqwest::IntoUrl) -> RequestBuilder {
+        self.inner
+            .client
+            .request(method, url)
+            .header(reqwest::header::COOKIE, self.inner.cookie.clone())
+            .header(reqwest::header::REFERER, self.inner.referer.clone())
+            .header(reqwest::header::USER_AGENT, USER_AGENT_HEADER_VALUE.clone())
+    }
+
+    /// Joins a path onto the base URL.
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.inner.base_url)
+    }
+
+    /// Returns the base URL used for API requests.
+    pub fn base_url(&self) -> &str {
+        &self.inner.base_url
+    }
+
+    /// Returns the host serving the images.
+    pub fn image_host(&self) -> &str {
+        &self.inner.image_host
+    }
+
+    /// Rewrites an image URL returned by the API (pointing at `i.pximg.net`)
+    /// to the configured image host.
+    ///
+    /// URLs on other hosts are returned unchanged.
+    pub fn image_url(&self, url: &str) -> Result<Url> {
+        let invalid = || crate::Error::InvalidArgument("Image URL seems to be invalid");
+        let mut url = Url::parse(url).map_err(|_| invalid())?;
+        if url.host_str() == Some("i.pximg.net") {
+            let host = Url::parse(&self.inner.image_host).map_err(|_| invalid())?;
+            let path = format!("{}{}", host.path().trim_end_matches('/'), url.path());
+            url.set_scheme(host.scheme()).map_err(|_| invalid())?;
+            url.set_host(host.host_str()).map_err(|_| invalid())?;
+            url.set_port(host.port()).map_err(|_| invalid())?;
+            url.set_path(&path);
+        }
+        Ok(url)
+    }
+
     /// Get the User ID of the logged in user.
     pub async fn self_user_id(&self) -> Result<Option<i32>> {
+        let _permit = self.inner.api_limiter.acquire().await;
         let resp = self
-            .client
-            .get(BASE_URL_HTTPS)
-            .header(reqwest::header::COOKIE, self.cookie.clone())
-            .header(reqwest::header::REFERER, REFERER_HEADER_VALUE.clone())
-            .header(reqwest::header::USER_AGENT, USER_AGENT_HEADER_VALUE.clone())
+            .request(Method::GET, &self.inner.base_url)
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
+        let resp = check_status(resp).await?;
         let headers = resp.headers();
         Ok(headers
             .get("x-userid")
@@ -97,27 +206,76 @@ impl PixivClient {
 
     /// Get the info of an user.
     pub fn user_info(&self, user_id: i32) -> GetRequest<'_, UserInfo> {
-        self.get(format!("{BASE_URL_HTTPS}/ajax/user/{user_id}?full=1"))
+        self.get(self.url(&format!("/ajax/user/{user_id}?full=1")))
     }
 
     /// Get the top works of an user.
     pub fn user_top_works(&self, user_id: i32) -> GetRequest<'_, UserTopWorks> {
-        self.get(format!("{BASE_URL_HTTPS}/ajax/user/{user_id}/profile/top"))
+        self.get(self.url(&format!("/ajax/user/{user_id}/profile/top")))
     }
 
     /// Get all the works of an user.
     pub fn user_all_works(&self, user_id: i32) -> GetRequest<'_, UserAllWorks> {
-        self.get(format!("{BASE_URL_HTTPS}/ajax/user/{user_id}/profile/all"))
+        self.get(self.url(&format!("/ajax/user/{user_id}/profile/all")))
     }
 
     /// Get the info of an illust.
     pub fn illust_info(&self, illust_id: i32) -> GetRequest<'_, IllustInfo> {
-        self.get(format!("{BASE_URL_HTTPS}/ajax/illust/{illust_id}"))
+        self.get(self.url(&format!("/ajax/illust/{illust_id}")))
     }
 
     /// Get pages of an illust.
     pub fn illust_pages(&self, illust_id: i32) -> GetRequest<'_, Vec<IllustImage>> {
-        self.get(format!("{BASE_URL_HTTPS}/ajax/illust/{illust_id}/pages"))
+        self.get(self.url(&format!("/ajax/illust/{illust_id}/pages")))
+    }
+
+    /// Get the ugoira metadata of an animated illust.
+    pub fn ugoira_meta(&self, illust_id: i32) -> GetRequest<'_, UgoiraMeta> {
+        self.get(self.url(&format!("/ajax/illust/{illust_id}/ugoira_meta")))
+    }
+
+    /// Get the info of a novel, including its text.
+    pub fn novel_info(&self, novel_id: i32) -> GetRequest<'_, NovelInfo> {
+        self.get(self.url(&format!("/ajax/novel/{novel_id}")))
+    }
+
+    /// Get the info of a novel series.
+    pub fn novel_series(&self, series_id: i32) -> GetRequest<'_, NovelSeries> {
+        self.get(self.url(&format!("/ajax/novel/series/{series_id}")))
+    }
+
+    /// Get a page of the novels in a novel series, in series order.
+    pub fn novel_series_content(
+        &self,
+        series_id: i32,
+        cursor: Offset,
+    ) -> GetRequest<'_, NovelSeriesContents> {
+        let Offset { offset, limit } = cursor;
+        self.get(self.url(&format!(
+            "/ajax/novel/series_content/{series_id}?limit={limit}&last_order={offset}&order_by=asc"
+        )))
+    }
+
+    /// Get all the novels in a novel series as a series of stream.
+    pub fn novel_series_stream(
+        &self,
+        series_id: i32,
+    ) -> PagedStream<'static, NovelSeriesContent, Offset> {
+        let client = self.clone();
+        PagedStream::new(Offset::new(30), move |cursor: Offset| {
+            let client = client.clone();
+            async move {
+                let items = client
+                    .novel_series_content(series_id, cursor)
+                    .await?
+                    .series_contents;
+                Ok(Page {
+                    next: cursor.next_page(items.len(), None),
+                    items,
+                    total: None,
+                })
+            }
+        })
     }
 
     /// Get the Pixiv ranking.
@@ -163,61 +321,37 @@ impl PixivClient {
             .as_ref()
             .map(|d| format!("&date={d}"))
             .unwrap_or_default();
-        Ok(self
-            .client
-            .get(format!(
-                "{BASE_URL_HTTPS}/ranking.php?format=json{mode}{content}{page}{date}",
-            ))
-            .header(reqwest::header::COOKIE, self.cookie.clone())
-            .header(reqwest::header::REFERER, REFERER_HEADER_VALUE.clone())
-            .header(reqwest::header::USER_AGENT, USER_AGENT_HEADER_VALUE.clone())
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<Ranking>()
-            .await?)
+        let url = Url::parse(&self.url(&format!(
+            "/ranking.php?format=json{mode}{content}{page}{date}"
+        )))
+        .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"))?;
+        self.get_json(url, &self.inner.retry, decode_json).await
     }
 
     /// Get the Pixiv ranking as a series of stream.
-    pub async fn ranking_stream(
+    pub fn ranking_stream(
         &self,
         mode: RankingMode,
         content: RankingContent,
         date: Option<String>,
-    ) -> impl Stream<Item = Result<RankingItem>> + '_ {
-        try_stream! {
-            let first = self._ranking(mode, content, &date, None).await?;
-            for content in first.contents {
-                yield content;
+    ) -> PagedStream<'static, RankingItem, i32> {
+        let client = self.clone();
+        PagedStream::new(1, move |page| {
+            let client = client.clone();
+            let date = date.clone();
+            async move {
+                let ranking = client._ranking(mode, content, &date, Some(page)).await?;
+                Ok(Page {
+                    items: ranking.contents,
+                    next: ranking.next,
+                    total: None,
+                })
             }
-            while let Some(next) = first.next {
-                let result = self._ranking(mode, content, &date, Some(next)).await?;
-                for content in result.contents {
-                    yield content;
-                }
-            }
-        }
+        })
     }
 
     /// Returns the client instance.
     pub fn client(&self) -> &reqwest::Client {
-        &self.client
-    }
-
-    #[allow(dead_code)]
-    async fn csrf_token(client: &Client) -> Result<String> {
-        let resp = client
-            .get(BASE_URL_HTTPS)
-            .send()
-            .await?
-            .error_for_status()?
-            .text()
-            .await?;
-        let re = Regex::new(r#"token":"([^"])"#).unwrap();
-        let caps = re
-            .captures(&resp)
-            .ok_or(crate::Error::Other("No CSRF Token Found"))?;
-        let token = &caps[1];
-        Ok(token.to_string())
+        &self.inner.client
     }
 }
@@ -0,0 +1,49 @@
+//! Like endpoints.
+
+use serde::{Deserialize, Serialize};
+
+use crate::PixivClient;
+
+#[derive(Serialize)]
+struct LikeIllust {
+    illust_id: String,
+}
+
+#[derive(Serialize)]
+struct LikeNovel {
+    novel_id: String,
+}
+
+#[derive(Deserialize)]
+struct Liked {
+    /// Whether the work was liked before this request.
+    is_liked: bool,
+}
+
+impl PixivClient {
+    /// Likes an illust.
+    ///
+    /// Returns `false` if the illust was already liked.
+    pub async fn like_illust(&self, illust_id: i32) -> crate::Result<bool> {
+        let liked: Liked = self
+            .post(self.url("/ajax/illusts/like"))
+            .json(&LikeIllust {
+                illust_id: illust_id.to_string(),
+            })
+            .await?;
+        Ok(!liked.is_liked)
+    }
+
+    /// Likes a novel.
+    ///
+    /// Returns `false` if the novel was already liked.
+    pub async fn like_novel(&self, novel_id: i32) -> crate::Result<bool> {
+        let liked: Liked = self
+            .post(self.url("/ajax/novels/like"))
+            .json(&LikeNovel {
+                novel_id: novel_id.to_string(),
+            })
+            .await?;
+        Ok(!liked.is_liked)
+    }
+}
@@ -0,0 +1,138 @@
+//! Client-side rate limits.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// A limit on how fast and how many requests are sent at once.
+///
+/// The rate is enforced with a token bucket: up to `burst` requests can be
+/// sent at once, then requests wait for the bucket to refill at the rate.
+///
+/// ## Example
+/// ```
+/// use pixrs::limit::RateLimit;
+///
+/// // 2 requests per second, with bursts of 5, and 4 requests at most in flight.
+/// let limit = RateLimit::per_second(2.0).burst(5).max_in_flight(4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RateLimit {
+    per_second: Option<f64>,
+    burst: u32,
+    max_in_flight: Option<usize>,
+}
+
+impl RateLimit {
+    /// Creates a limit of no more than `per_second` requests per second.
+    ///
+    /// `per_second` must be positive, [`crate::PixivClientBuilder::build`]
+    /// fails otherwise.
+    pub fn per_second(per_second: f64) -> Self {
+        RateLimit {
+            per_second: Some(per_second),
+            burst: 1,
+            max_in_flight: None,
+        }
+    }
+
+    /// Creates no limit, combine with [`RateLimit::max_in_flight`] to only
+    /// limit the requests in flight.
+    pub fn unlimited() -> Self {
+        RateLimit::default()
+    }
+
+    /// Sets how many requests can be sent at once before the rate applies.
+    ///
+    /// Defaults to 1.
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Sets how many requests can be in flight at once.
+    ///
+    /// Image downloads are in flight until their stream is dropped.
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight.max(1));
+        self
+    }
+
+    /// Checks that the rate is a positive number.
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        match self.per_second {
+            Some(per_second) if !(per_second.is_finite() && per_second > 0.0) => Err(
+                crate::Error::InvalidArgument("Rate limit must be a positive number"),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    per_second: f64,
+    refilled: Instant,
+}
+
+/// Enforces a [`RateLimit`], shared by all the requests of a budget.
+pub(crate) struct Limiter {
+    bucket: Option<Mutex<Bucket>>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+/// Holds a place among the requests in flight until dropped.
+pub(crate) struct Permit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Limiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        let bucket = limit.per_second.map(|per_second| {
+            let capacity = f64::from(limit.burst.max(1));
+            Mutex::new(Bucket {
+                tokens: capacity,
+                capacity,
+                per_second,
+                refilled: Instant::now(),
+            })
+        });
+        Limiter {
+            bucket,
+            in_flight: limit
+                .max_in_flight
+                .map(|max_in_flight| Arc::new(Semaphore::new(max_in_flight))),
+        }
+    }
+
+    /// Waits until a request can be sent.
+    pub(crate) async fn acquire(&self) -> Permit {
+        let permit = match &self.in_flight {
+            // The semaphore is never closed.
+            Some(in_flight) => Arc::clone(in_flight).acquire_owned().await.ok(),
+            None => None,
+        };
+        if let Some(bucket) = &self.bucket {
+            loop {
+                let wait = {
+                    let mut bucket = bucket.lock().unwrap();
+                    let now = Instant::now();
+                    let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+                    bucket.tokens =
+                        (bucket.tokens + elapsed * bucket.per_second).min(bucket.capacity);
+                    bucket.refilled = now;
+                    if bucket.tokens >= 1.0 {
+                        bucket.tokens -= 1.0;
+                        break;
+                    }
+                    Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.per_second)
+                };
+                tokio::time::sleep(wait).await;
+            }
+        }
+        Permit { _permit: permit }
+    }
+}
@@ -0,0 +1,29 @@
+//! Novel utilities.
+
+pub mod markup;
+
+use crate::novel::markup::{Document, Image};
+use crate::NovelInfo;
+
+impl NovelInfo {
+    /// Parses the text of the novel into a [`Document`].
+    pub fn document(&self) -> Document {
+        Document::parse(&self.content)
+    }
+
+    /// Returns the URL of an image referenced in the novel text, if it is
+    /// known from the novel info.
+    ///
+    /// Only uploaded images are resolved, as illusts need to be fetched
+    /// separately.
+    pub fn image_url(&self, image: &Image) -> Option<String> {
+        match image {
+            Image::Uploaded { image_id } => self
+                .text_embedded_images
+                .get(image_id)
+                .and_then(|image| image.urls.get("original"))
+                .cloned(),
+            Image::Pixiv { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,533 @@
+//! Parser and renderers for the Pixiv novel markup.
+//!
+//! The markup supports the following tags:
+//!
+//! * `[newpage]`: Starts a new page.
+//! * `[chapter:Title]`: A chapter heading.
+//! * `[[rb:漢字 > かんじ]]`: Ruby text.
+//! * `[pixivimage:123-2]`: An illust (and optionally its page) on Pixiv.
+//! * `[uploadedimage:123]`: An image uploaded with the novel.
+//! * `[[jumpuri:Title > https://example.com]]`: A link.
+//! * `[jump:2]`: A link to a page of the novel.
+//!
+//! Malformed tags are kept as text.
+
+use std::fmt::Write;
+
+/// A parsed novel text.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Document {
+    /// The pages of the novel.
+    pub pages: Vec<Page>,
+}
+
+/// A page of a novel.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Page {
+    /// The content of the page.
+    pub nodes: Vec<Node>,
+}
+
+/// A node in a novel page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Node {
+    /// Plain text.
+    Text(String),
+    /// A line break.
+    Newline,
+    /// Text annotated with its reading.
+    Ruby {
+        /// The annotated text.
+        base: String,
+        /// The reading of the text.
+        reading: String,
+    },
+    /// A link to an URL.
+    Link {
+        /// The title of the link.
+        title: String,
+        /// The URL the link points to.
+        url: String,
+    },
+    /// A link to a page of the novel, starting from 1.
+    Jump(u32),
+    /// A chapter heading, containing only text and ruby.
+    Chapter(Vec<Node>),
+    /// An image.
+    Image(Image),
+}
+
+/// An image referenced in a novel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Image {
+    /// An illust on Pixiv.
+    Pixiv {
+        /// The ID of the illust.
+        illust_id: i32,
+        /// The page of the illust, starting from 1.
+        page: Option<u32>,
+    },
+    /// An image uploaded with the novel.
+    Uploaded {
+        /// The ID of the image.
+        image_id: String,
+    },
+}
+
+impl Document {
+    /// Parses a novel text.
+    pub fn parse(text: &str) -> Self {
+        let text = text.replace("\r\n", "\n");
+        let pages = text
+            .split("[newpage]")
+            .map(|page| {
+                let page = page.strip_prefix('\n').unwrap_or(page);
+                let page = page.strip_suffix('\n').unwrap_or(page);
+                Page {
+                    nodes: parse_nodes(page),
+                }
+            })
+            .collect();
+        Document { pages }
+    }
+
+    /// Renders the document to HTML, linking illusts to Pixiv and omitting
+    /// uploaded images.
+    pub fn to_html(&self) -> String {
+        self.render_html(|_| None)
+    }
+
+    /// Renders the document to HTML, using `image_url` to find the URL of
+    /// images.
+    ///
+    /// All text is escaped, and only `http` and `https` URLs are kept.
+    pub fn render_html(&self, image_url: impl Fn(&Image) -> Option<String>) -> String {
+        let mut out = String::new();
+        for (index, page) in self.pages.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                r#"<section class="novel-page" id="page-{}">"#,
+                index + 1
+            );
+            let mut open = false;
+            for node in &page.nodes {
+                match node {
+                    Node::Newline => {
+                        if open {
+                            out.push_str("</p>\n");
+                            open = false;
+                        } else {
+                            out.push_str("<p><br /></p>\n");
+                        }
+                    }
+                    Node::Chapter(_) | Node::Image(_) => {
+                        if open {
+                            out.push_str("</p>\n");
+                            open = false;
+                        }
+                        html_block(&mut out, node, &image_url);
+                    }
+                    _ => {
+                        if !open {
+                            out.push_str("<p>");
+                            open = true;
+                        }
+                        html_inline(&mut out, node);
+                    }
+                }
+            }
+            if open {
+                out.push_str("</p>\n");
+            }
+            out.push_str("</section>\n");
+        }
+        out
+    }
+
+    /// Renders the document to Markdown, linking illusts to Pixiv and
+    /// omitting uploaded images.
+    pub fn to_markdown(&self) -> String {
+        self.render_markdown(|_| None)
+    }
+
+    /// Renders the document to Markdown, using `image_url` to find the URL
+    /// of images.
+    ///
+    /// All text is escaped, and only `http` and `https` URLs are kept.
+    pub fn render_markdown(&self, image_url: impl Fn(&Image) -> Option<String>) -> String {
+        let mut out = String::new();
+        for (index, page) in self.pages.iter().enumerate() {
+            if index > 0 {
+                out.push_str("\n\n---\n\n");
+            }
+            let _ = write!(out, r#"<a id="page-{}"></a>"#, index + 1);
+            out.push_str("\n\n");
+            for node in &page.nodes {
+                match node {
+                    Node::Newline => {
+                        if !out.ends_with("\n\n") {
+                            out.push_str("\n\n");
+                        }
+                    }
+                    Node::Chapter(title) => {
+                        if !out.ends_with("\n\n") {
+                            out.push_str("\n\n");
+                        }
+                        out.push_str("## ");
+                        for node in title {
+                            markdown_inline(&mut out, node);
+                        }
+                        out.push_str("\n\n");
+                    }
+                    Node::Image(image) => {
+                        if !out.ends_with("\n\n") {
+                            out.push_str("\n\n");
+                        }
+                        let len = out.len();
+                        markdown_image(&mut out, image, image_url(image));
+                        if out.len() > len {
+                            out.push_str("\n\n");
+                        }
+                    }
+                    _ => markdown_inline(&mut out, node),
+                }
+            }
+        }
+        let len = out.trim_end().len();
+        out.truncate(len);
+        out.push('\n');
+        out
+    }
+}
+
+fn parse_nodes(input: &str) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut text = String::new();
+    let mut rest = input;
+    while let Some(ch) = rest.chars().next() {
+        if ch == '[' {
+            if let Some((node, after)) = parse_tag(rest) {
+                flush_text(&mut text, &mut nodes);
+                let block = matches!(node, Node::Chapter(_) | Node::Image(_));
+                nodes.push(node);
+                rest = if block {
+                    after.strip_prefix('\n').unwrap_or(after)
+                } else {
+                    after
+                };
+                continue;
+            }
+        }
+        if ch == '\n' {
+            flush_text(&mut text, &mut nodes);
+            nodes.push(Node::Newline);
+        } else {
+            text.push(ch);
+        }
+        rest = &rest[ch.len_utf8()..];
+    }
+    flush_text(&mut text, &mut nodes);
+    nodes
+}
+
+/// Parses a chapter title, where only ruby is recognized and other tags
+/// are kept as text.
+fn parse_title(input: &str) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut text = String::new();
+    let mut rest = input;
+    while let Some(ch) = rest.chars().next() {
+        if rest.starts_with("[[rb:") {
+            if let Some((node, after)) = parse_tag(rest) {
+                flush_text(&mut text, &mut nodes);
+                nodes.push(node);
+                rest = after;
+                continue;
+            }
+        }
+        text.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    flush_text(&mut text, &mut nodes);
+    nodes
+}
+
+fn flush_text(text: &mut String, nodes: &mut Vec<Node>) {
+    if !text.is_empty() {
+        nodes.push(Node::Text(std::mem::take(text)));
+    }
+}
+
+/// Parses the tag at the start of `input`, returning the node and the
+/// remaining input.
+fn parse_tag(input: &str) -> Option<(Node, &str)> {
+    if let Some(rest) = input.strip_prefix("[[rb:") {
+        let (inner, after) = rest.split_once("]]")?;
+        let (base, reading) = inner.split_once('>')?;
+        let (base, reading) = (base.trim(), reading.trim());
+        if inner.contains('\n') || base.is_empty() {
+            return None;
+        }
+        let node = Node::Ruby {
+            base: base.to_string(),
+            reading: reading.to_string(),
+        };
+        return Some((node, after));
+    }
+    if let Some(rest) = input.strip_prefix("[[jumpuri:") {
+        let (inner, after) = rest.split_once("]]")?;
+        let (title, url) = inner.rsplit_once('>')?;
+        if inner.contains('\n') {
+            return None;
+        }
+        let node = Node::Link {
+            title: title.trim().to_string(),
+            url: url.trim().to_string(),
+        };
+        return Some((node, after));
+    }
+    if let Some(rest) = input.strip_prefix("[chapter:") {
+        let end = chapter_end(rest)?;
+        let title = parse_title(&rest[..end]);
+        return Some((Node::Chapter(title), &rest[end + 1..]));
+    }
+    if let Some(rest) = input.strip_prefix("[pixivimage:") {
+        let (inner, after) = rest.split_once(']')?;
+        let (illust_id, page) = match inner.split_once('-') {
+            Some((illust_id, page)) => (illust_id, Some(page.trim().parse().ok()?)),
+            None => (inner, None),
+        };
+        let illust_id = illust_id.trim().parse().ok()?;
+        return Some((Node::Image(Image::Pixiv { illust_id, page }), after));
+    }
+    if let Some(rest) = input.strip_prefix("[uploadedimage:") {
+        let (inner, after) = rest.split_once(']')?;
+        let image_id = inner.trim();
+        if image_id.is_empty() || !image_id.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let image = Image::Uploaded {
+            image_id: image_id.to_string(),
+        };
+        return Some((Node::Image(image), after));
+    }
+    if let Some(rest) = input.strip_prefix("[jump:") {
+        let (inner, after) = rest.split_once(']')?;
+        return Some((Node::Jump(inner.trim().parse().ok()?), after));
+    }
+    None
+}
+
+/// Finds the `]` closing a chapter, skipping over ruby inside the title.
+fn chapter_end(input: &str) -> Option<usize> {
+    let mut index = 0;
+    while index < input.len() {
+        let rest = &input[index..];
+        if rest.starts_with("[[") {
+            index += rest.find("]]")? + 2;
+            continue;
+        }
+        let ch = rest.chars().next()?;
+        match ch {
+            ']' => return Some(index),
+            '\n' => return None,
+            _ => index += ch.len_utf8(),
+        }
+    }
+    None
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let lower = url.trim_start().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+fn illust_link(illust_id: i32, page: Option<u32>) -> String {
+    match page {
+        Some(page) => format!("https://www.pixiv.net/artworks/{illust_id}#{page}"),
+        None => format!("https://www.pixiv.net/artworks/{illust_id}"),
+    }
+}
+
+fn escape_html(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+fn html_inline(out: &mut String, node: &Node) {
+    match node {
+        Node::Text(text) => escape_html(out, text),
+        Node::Ruby { base, reading } => {
+            out.push_str("<ruby>");
+            escape_html(out, base);
+            out.push_str("<rp>(</rp><rt>");
+            escape_html(out, reading);
+            out.push_str("</rt><rp>)</rp></ruby>");
+        }
+        Node::Link { title, url } if is_safe_url(url) => {
+            out.push_str(r#"<a href=""#);
+            escape_html(out, url.trim());
+            out.push_str(r#"" rel="noopener noreferrer" target="_blank">"#);
+            escape_html(out, title);
+            out.push_str("</a>");
+        }
+        Node::Link { title, .. } => escape_html(out, title),
+        Node::Jump(page) => {
+            let _ = write!(out, r##"<a href="#page-{page}">Page {page}</a>"##);
+        }
+        _ => {}
+    }
+}
+
+fn html_block(out: &mut String, node: &Node, image_url: &impl Fn(&Image) -> Option<String>) {
+    match node {
+        Node::Chapter(title) => {
+            out.push_str(r#"<h2 class="novel-chapter">"#);
+            for node in title {
+                html_inline(out, node);
+            }
+            out.push_str("</h2>\n");
+        }
+        Node::Image(image) => {
+            let url = image_url(image).filter(|url| is_safe_url(url));
+            match (image, url) {
+                (Image::Pixiv { illust_id, page }, url) => {
+                    out.push_str(r#"<figure class="novel-image"><a href=""#);
+                    escape_html(out, &illust_link(*illust_id, *page));
+                    out.push_str(r#"" rel="noopener noreferrer" target="_blank">"#);
+                    match url {
+                        Some(url) => {
+                            out.push_str(r#"<img src=""#);
+                            escape_html(out, &url);
+                            let _ = write!(out, r#"" alt="illust {illust_id}" />"#);
+                        }
+                        None => {
+                            let _ = write!(out, "illust {illust_id}");
+                        }
+                    }
+                    out.push_str("</a></figure>\n");
+                }
+                (Image::Uploaded { .. }, Some(url)) => {
+                    out.push_str(r#"<figure class="novel-image"><img src=""#);
+                    escape_html(out, &url);
+                    out.push_str(r#"" alt="" /></figure>"#);
+                    out.push('\n');
+                }
+                (Image::Uploaded { .. }, None) => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn escape_markdown(out: &mut String, text: &str) {
+    let text = if out.is_empty() || out.ends_with('\n') {
+        escape_line_start(out, text)
+    } else {
+        text
+    };
+    for ch in text.chars() {
+        if matches!(
+            ch,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~' | '!' | '&'
+        ) {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+}
+
+/// Escapes what would start a list, a heading underline, a thematic break or
+/// an indented code block at the start of a line, returning the text left to
+/// escape.
+fn escape_line_start<'a>(out: &mut String, text: &'a str) -> &'a str {
+    // Indentation is written as an entity, so nothing after it starts a block.
+    if let Some(rest) = text.strip_prefix(' ') {
+        out.push_str("&#32;");
+        return rest;
+    }
+    if let Some(rest) = text.strip_prefix('\t') {
+        out.push_str("&#9;");
+        return rest;
+    }
+    if text.starts_with(['-', '+', '=']) {
+        out.push('\\');
+        return text;
+    }
+    let digits = text.len()
+        - text
+            .trim_start_matches(|ch: char| ch.is_ascii_digit())
+            .len();
+    if digits > 0 && text[digits..].starts_with(['.', ')']) {
+        out.push_str(&text[..digits]);
+        out.push('\\');
+        return &text[digits..];
+    }
+    text
+}
+
+fn markdown_url(url: &str) -> String {
+    url.trim()
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+fn markdown_inline(out: &mut String, node: &Node) {
+    match node {
+        Node::Text(text) => escape_markdown(out, text),
+        Node::Ruby { base, reading } => {
+            escape_markdown(out, base);
+            out.push('（');
+            escape_markdown(out, reading);
+            out.push('）');
+        }
+        Node::Link { title, url } if is_safe_url(url) => {
+            out.push('[');
+            escape_markdown(out, title);
+            out.push_str("](");
+            out.push_str(&markdown_url(url));
+            out.push(')');
+        }
+        Node::Link { title, .. } => escape_markdown(out, title),
+        Node::Jump(page) => {
+            let _ = write!(out, "[Page {page}](#page-{page})");
+        }
+        _ => {}
+    }
+}
+
+fn markdown_image(out: &mut String, image: &Image, url: Option<String>) {
+    let url = url.filter(|url| is_safe_url(url));
+    match (image, url) {
+        (Image::Pixiv { illust_id, page }, Some(url)) => {
+            let _ = write!(
+                out,
+                "[![illust {illust_id}]({})]({})",
+                markdown_url(&url),
+                illust_link(*illust_id, *page)
+            );
+        }
+        (Image::Pixiv { illust_id, page }, None) => {
+            let _ = write!(
+                out,
+                "[illust {illust_id}]({})",
+                illust_link(*illust_id, *page)
+            );
+        }
+        (Image::Uploaded { .. }, Some(url)) => {
+            let _ = write!(out, "![]({})", markdown_url(&url));
+        }
+        (Image::Uploaded { .. }, None) => {}
+    }
+}
@@ -0,0 +1,178 @@
+//! Paginated streams.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::Stream;
+
+/// A page of items returned by a paginated endpoint.
+#[derive(Debug, Clone)]
+pub struct Page<T, C> {
+    /// The items in this page.
+    pub items: Vec<T>,
+    /// The cursor of the next page, `None` if this is the last page.
+    pub next: Option<C>,
+    /// The total count of items, if the endpoint reports it.
+    pub total: Option<usize>,
+}
+
+/// A cursor for endpoints paginated with `offset` and `limit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Offset {
+    /// The offset of the first item in the page.
+    pub offset: usize,
+    /// The maximum count of items in the page.
+    pub limit: usize,
+}
+
+impl Offset {
+    /// Creates a cursor pointing at the first page.
+    pub fn new(limit: usize) -> Self {
+        Offset { offset: 0, limit }
+    }
+
+    /// Returns the cursor of the page following this one, given how many
+    /// items this page had and the total count if known.
+    pub fn next_page(self, fetched: usize, total: Option<usize>) -> Option<Self> {
+        let offset = self.offset + fetched;
+        match total {
+            Some(total) if offset >= total => None,
+            _ if fetched == 0 => None,
+            _ => Some(Offset {
+                offset,
+                limit: self.limit,
+            }),
+        }
+    }
+}
+
+/// A position in a [`PagedStream`], to resume it without losing or
+/// repeating items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position<C> {
+    /// The cursor of the page holding the next item.
+    pub cursor: C,
+    /// The count of items of that page already yielded.
+    pub skip: usize,
+}
+
+type PageFuture<'a, T, C> = Pin<Box<dyn Future<Output = crate::Result<Page<T, C>>> + Send + 'a>>;
+
+/// A [`Stream`] of items driven by a page cursor.
+///
+/// The stream fetches the next page once all items of the current page have
+/// been yielded, and ends when a page has no next cursor. Pages without
+/// items, e.g. when all their works are hidden, do not end the stream.
+/// Polling again after an error retries the failed page.
+///
+/// Streams returned by [`crate::PixivClient`] hold a handle to the client,
+/// so they can be moved into `tokio::spawn`.
+pub struct PagedStream<'a, T, C> {
+    fetch: Box<dyn FnMut(C) -> PageFuture<'a, T, C> + Send + 'a>,
+    pending: Option<PageFuture<'a, T, C>>,
+    items: VecDeque<T>,
+    /// The cursor of the page in `items`.
+    page: Option<C>,
+    /// The count of items of `page` already yielded.
+    taken: usize,
+    /// The cursor of the next page to fetch.
+    next: Option<C>,
+    /// The count of items to drop from the next fetched page.
+    skip: usize,
+    total: Option<usize>,
+}
+
+impl<'a, T, C: Clone> PagedStream<'a, T, C> {
+    /// Creates a stream starting at `start`, using `fetch` to get a page
+    /// from its cursor.
+    pub fn new<F, Fut>(start: C, mut fetch: F) -> Self
+    where
+        F: FnMut(C) -> Fut + Send + 'a,
+        Fut: Future<Output = crate::Result<Page<T, C>>> + Send + 'a,
+    {
+        PagedStream {
+            fetch: Box::new(move |cursor| Box::pin(fetch(cursor))),
+            pending: None,
+            items: VecDeque::new(),
+            page: None,
+            taken: 0,
+            next: Some(start),
+            skip: 0,
+            total: None,
+        }
+    }
+
+    /// Restarts the stream from the given position, dropping buffered
+    /// items.
+    ///
+    /// Use this with a position saved from [`PagedStream::cursor`] to resume
+    /// an interrupted stream.
+    pub fn resume_from(mut self, position: Position<C>) -> Self {
+        self.pending = None;
+        self.items.clear();
+        self.page = None;
+        self.taken = 0;
+        self.next = Some(position.cursor);
+        self.skip = position.skip;
+        self.total = None;
+        self
+    }
+
+    /// Returns the position of the next item to be yielded, `None` if all
+    /// pages have been fetched and yielded.
+    pub fn cursor(&self) -> Option<Position<C>> {
+        match &self.page {
+            Some(page) if !self.items.is_empty() => Some(Position {
+                cursor: page.clone(),
+                skip: self.taken,
+            }),
+            _ => self.next.clone().map(|cursor| Position {
+                cursor,
+                skip: self.skip,
+            }),
+        }
+    }
+
+    /// Returns the total count of items reported by the last fetched page.
+    pub fn total(&self) -> Option<usize> {
+        self.total
+    }
+}
+
+impl<T, C> Unpin for PagedStream<'_, T, C> {}
+
+impl<T, C: Clone> Stream for PagedStream<'_, T, C> {
+    type Item = crate::Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(item) = this.items.pop_front() {
+                this.taken += 1;
+                return Poll::Ready(Some(Ok(item)));
+            }
+            if let Some(pending) = this.pending.as_mut() {
+                let result = ready!(pending.as_mut().poll(cx));
+                this.pending = None;
+                let page = match result {
+                    Ok(page) => page,
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                };
+                if page.total.is_some() {
+                    this.total = page.total;
+                }
+                this.page = std::mem::replace(&mut this.next, page.next);
+                this.taken = this.skip;
+                this.items.extend(page.items.into_iter().skip(this.skip));
+                this.skip = 0;
+                continue;
+            }
+            match this.next.clone() {
+                Some(cursor) => this.pending = Some((this.fetch)(cursor)),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
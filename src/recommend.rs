@@ -0,0 +1,107 @@
+//! Recommendations.
+
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+use crate::paginate::{Page, PagedStream};
+use crate::{IllustProfile, PixivClient};
+
+/// How many recommended illusts are fetched at once.
+const BATCH_SIZE: usize = 18;
+
+/// The cursor of [`PixivClient::illust_recommend`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecommendCursor {
+    /// The first page, fetched from the seed illust.
+    Init,
+    /// The IDs of the recommended illusts not fetched yet.
+    Next(Vec<i32>),
+}
+
+/// The restriction of discovered illusts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiscoveryMode {
+    /// All illusts.
+    #[default]
+    All,
+    /// Only illusts for all ages.
+    Safe,
+    /// Only R-18 illusts.
+    R18,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecommendBody {
+    #[serde(deserialize_with = "crate::de::skip_ad_containers")]
+    illusts: Vec<IllustProfile>,
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[serde(default)]
+    next_ids: Vec<i32>,
+}
+
+#[derive(Deserialize)]
+struct DiscoveryBody {
+    thumbnails: Thumbnails,
+}
+
+#[derive(Deserialize)]
+struct Thumbnails {
+    illust: Vec<IllustProfile>,
+}
+
+impl PixivClient {
+    /// Get the illusts recommended from an illust as a stream.
+    ///
+    /// The remaining recommendations are fetched in batches as the stream
+    /// is polled.
+    pub fn illust_recommend(
+        &self,
+        illust_id: i32,
+    ) -> PagedStream<'static, IllustProfile, RecommendCursor> {
+        let client = self.clone();
+        PagedStream::new(RecommendCursor::Init, move |cursor: RecommendCursor| {
+            let client = client.clone();
+            async move {
+                let (items, rest) = match cursor {
+                    RecommendCursor::Init => {
+                        let url = client.url(&format!(
+                            "/ajax/illust/{illust_id}/recommend/init?limit={BATCH_SIZE}"
+                        ));
+                        let body: RecommendBody = client.get(url).await?;
+                        (body.illusts, body.next_ids)
+                    }
+                    RecommendCursor::Next(mut ids) => {
+                        let rest = ids.split_off(ids.len().min(BATCH_SIZE));
+                        let query = ids
+                            .iter()
+                            .map(|id| format!("illust_ids[]={id}"))
+                            .collect::<Vec<_>>()
+                            .join("&");
+                        let url = client.url(&format!("/ajax/illust/recommend/illusts?{query}"));
+                        (client.get::<RecommendBody>(url).await?.illusts, rest)
+                    }
+                };
+                Ok(Page {
+                    items,
+                    next: (!rest.is_empty()).then_some(RecommendCursor::Next(rest)),
+                    total: None,
+                })
+            }
+        })
+    }
+
+    /// Get the illusts on the discovery page, picked for the account holder.
+    pub async fn discovery(&self, mode: DiscoveryMode) -> crate::Result<Vec<IllustProfile>> {
+        let mode = match mode {
+            DiscoveryMode::All => "all",
+            DiscoveryMode::Safe => "safe",
+            DiscoveryMode::R18 => "r18",
+        };
+        let body: DiscoveryBody = self
+            .get(self.url(&format!("/ajax/discovery/artworks?mode={mode}&limit=60")))
+            .await?;
+        Ok(body.thumbnails.illust)
+    }
+}
@@ -0,0 +1,120 @@
+//! Retrying failed requests.
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::time::{Duration, SystemTime};
+
+/// A policy to retry failed requests with exponential backoff.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use pixrs::retry::RetryPolicy;
+///
+/// let policy = RetryPolicy::default()
+///     .max_attempts(5)
+///     .backoff(Duration::from_secs(1), Duration::from_secs(60));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retry_if: fn(&crate::Error) -> bool,
+}
+
+impl Default for RetryPolicy {
+    /// Tries 3 times, waiting from 500 milliseconds up to 30 seconds with
+    /// jitter, retrying errors where [`crate::Error::is_retryable`] is true.
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retry_if: crate::Error::is_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy that never retries.
+    pub fn never() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets how many times a request is sent at most, including the first
+    /// attempt.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the wait before the first retry, doubled after each retry up
+    /// to `max`.
+    ///
+    /// `max` also caps the wait asked by `Retry-After`.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets whether to wait a random part of the backoff, so clients do not
+    /// retry all at once.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets which errors are retried.
+    ///
+    /// Defaults to [`crate::Error::is_retryable`].
+    pub fn retry_if(mut self, retry_if: fn(&crate::Error) -> bool) -> Self {
+        self.retry_if = retry_if;
+        self
+    }
+
+    /// Returns how long to wait before the retry following `attempt`
+    /// (starting from 1), honoring the `Retry-After` of the error up to the
+    /// maximum backoff.
+    fn delay(&self, attempt: u32, err: &crate::Error) -> Duration {
+        if let Some(retry_after) = err.retry_after() {
+            return retry_after.min(self.max_backoff);
+        }
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(self.max_backoff);
+        if self.jitter {
+            // Waits between half and all of the backoff.
+            let random = RandomState::new().hash_one(SystemTime::now()) % 1000;
+            backoff / 2 + (backoff / 2).mul_f64(random as f64 / 1000.0)
+        } else {
+            backoff
+        }
+    }
+
+    /// Runs `f` until it succeeds, fails with an error that is not retried,
+    /// or the attempts are used up.
+    pub(crate) async fn run<T, F, Fut>(&self, mut f: F) -> crate::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = crate::Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let err = match f().await {
+                Err(err) if attempt < self.max_attempts && (self.retry_if)(&err) => err,
+                result => return result,
+            };
+            tokio::time::sleep(self.delay(attempt, &err)).await;
+            attempt += 1;
+        }
+    }
+}
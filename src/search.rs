@@ -0,0 +1,402 @@
+//! Search queries.
+
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use time::macros::format_description;
+use time::Date;
+
+use crate::paginate::{Page, PagedStream};
+use crate::{IllustProfile, NovelProfile, PixivClient, SearchResults};
+
+/// How the search words are matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMatch {
+    /// Match tags exactly.
+    ExactTag,
+    /// Match tags partially.
+    #[default]
+    PartialTag,
+    /// Match the title and caption.
+    TitleCaption,
+}
+
+/// The order of search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchOrder {
+    /// Newest first.
+    #[default]
+    Newest,
+    /// Oldest first.
+    Oldest,
+    /// Most popular first. Requires Pixiv Premium.
+    Popular,
+}
+
+/// The restriction of search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// All works.
+    #[default]
+    All,
+    /// Only works for all ages.
+    Safe,
+    /// Only R-18 works.
+    R18,
+}
+
+/// The type of artworks in search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchType {
+    /// All artworks.
+    #[default]
+    All,
+    /// Illusts and ugoiras.
+    IllustAndUgoira,
+    /// Only illusts.
+    Illust,
+    /// Only mangas.
+    Manga,
+    /// Only ugoiras.
+    Ugoira,
+}
+
+/// The aspect ratio of artworks in search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AspectRatio {
+    /// Wider than high.
+    Landscape,
+    /// Higher than wide.
+    Portrait,
+    /// Square.
+    Square,
+}
+
+/// A search query.
+///
+/// ## Example
+/// ```
+/// use pixrs::search::{SearchOrder, SearchQuery};
+///
+/// // Searches `アークナイツ (ウタゲ OR シデロカ) -AI生成`.
+/// let query = SearchQuery::new("アークナイツ")
+///     .or(["ウタゲ", "シデロカ"])
+///     .exclude("AI生成")
+///     .order(SearchOrder::Oldest);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    words: Vec<String>,
+    excluded: Vec<String>,
+    match_mode: SearchMatch,
+    order: SearchOrder,
+    mode: SearchMode,
+    search_type: SearchType,
+    start_date: Option<Date>,
+    end_date: Option<Date>,
+    min_width: Option<u32>,
+    max_width: Option<u32>,
+    min_height: Option<u32>,
+    max_height: Option<u32>,
+    ratio: Option<AspectRatio>,
+    tool: Option<String>,
+    exclude_ai: bool,
+}
+
+impl SearchQuery {
+    /// Creates a query searching for `word`.
+    pub fn new(word: impl Into<String>) -> Self {
+        SearchQuery {
+            words: vec![word.into()],
+            ..Default::default()
+        }
+    }
+
+    /// Requires `word` to also match.
+    pub fn and(mut self, word: impl Into<String>) -> Self {
+        self.words.push(word.into());
+        self
+    }
+
+    /// Requires any of `words` to also match.
+    pub fn or<S: Into<String>>(mut self, words: impl IntoIterator<Item = S>) -> Self {
+        let words: Vec<String> = words.into_iter().map(Into::into).collect();
+        match words.len() {
+            0 => {}
+            1 => self.words.extend(words),
+            _ => self.words.push(format!("({})", words.join(" OR "))),
+        }
+        self
+    }
+
+    /// Excludes results matching `word`.
+    pub fn exclude(mut self, word: impl Into<String>) -> Self {
+        self.excluded.push(word.into());
+        self
+    }
+
+    /// Sets how the words are matched.
+    pub fn match_mode(mut self, match_mode: SearchMatch) -> Self {
+        self.match_mode = match_mode;
+        self
+    }
+
+    /// Sets the order of the results.
+    pub fn order(mut self, order: SearchOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the restriction of the results.
+    pub fn mode(mut self, mode: SearchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the type of artworks.
+    ///
+    /// Used by [`PixivClient::search_artworks`], and by
+    /// [`PixivClient::search_illustrations`] to narrow the results to
+    /// [`SearchType::Illust`] or [`SearchType::Ugoira`]. Ignored otherwise.
+    pub fn search_type(mut self, search_type: SearchType) -> Self {
+        self.search_type = search_type;
+        self
+    }
+
+    /// Only includes works created in the date range, both ends inclusive.
+    pub fn date_range(mut self, start: Option<Date>, end: Option<Date>) -> Self {
+        self.start_date = start;
+        self.end_date = end;
+        self
+    }
+
+    /// Only includes works with a width in the range, in pixels.
+    pub fn width(mut self, min: Option<u32>, max: Option<u32>) -> Self {
+        self.min_width = min;
+        self.max_width = max;
+        self
+    }
+
+    /// Only includes works with a height in the range, in pixels.
+    pub fn height(mut self, min: Option<u32>, max: Option<u32>) -> Self {
+        self.min_height = min;
+        self.max_height = max;
+        self
+    }
+
+    /// Only includes works with the aspect ratio.
+    pub fn ratio(mut self, ratio: AspectRatio) -> Self {
+        self.ratio = Some(ratio);
+        self
+    }
+
+    /// Only includes works made with `tool`, e.g. `CLIP STUDIO PAINT`.
+    pub fn tool(mut self, tool: impl Into<String>) -> Self {
+        self.tool = Some(tool.into());
+        self
+    }
+
+    /// Excludes AI-generated works.
+    pub fn exclude_ai(mut self, exclude_ai: bool) -> Self {
+        self.exclude_ai = exclude_ai;
+        self
+    }
+
+    /// Returns the search words as typed in the Pixiv search box.
+    pub fn word(&self) -> String {
+        let excluded = self.excluded.iter().map(|word| format!("-{word}"));
+        self.words
+            .iter()
+            .cloned()
+            .chain(excluded)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn url(&self, base: &str, search_type: Option<SearchType>, page: u32) -> crate::Result<Url> {
+        let word = self.word();
+        let mut url = Url::parse(base)
+            .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"))?;
+        url.path_segments_mut()
+            .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"))?
+            .push(&word);
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("word", &word);
+            query.append_pair(
+                "order",
+                match self.order {
+                    SearchOrder::Newest => "date_d",
+                    SearchOrder::Oldest => "date",
+                    SearchOrder::Popular => "popular_d",
+                },
+            );
+            query.append_pair(
+                "mode",
+                match self.mode {
+                    SearchMode::All => "all",
+                    SearchMode::Safe => "safe",
+                    SearchMode::R18 => "r18",
+                },
+            );
+            query.append_pair("p", &page.to_string());
+            query.append_pair(
+                "s_mode",
+                match self.match_mode {
+                    SearchMatch::ExactTag => "s_tag_full",
+                    SearchMatch::PartialTag => "s_tag",
+                    SearchMatch::TitleCaption => "s_tc",
+                },
+            );
+            if let Some(search_type) = search_type {
+                query.append_pair(
+                    "type",
+                    match search_type {
+                        SearchType::All => "all",
+                        SearchType::IllustAndUgoira => "illust_and_ugoira",
+                        SearchType::Illust => "illust",
+                        SearchType::Manga => "manga",
+                        SearchType::Ugoira => "ugoira",
+                    },
+                );
+            }
+            let format = format_description!("[year]-[month]-[day]");
+            if let Some(date) = self.start_date {
+                query.append_pair("scd", &date.format(format).unwrap_or_default());
+            }
+            if let Some(date) = self.end_date {
+                query.append_pair("ecd", &date.format(format).unwrap_or_default());
+            }
+            for (key, value) in [
+                ("wlt", self.min_width),
+                ("wgt", self.max_width),
+                ("hlt", self.min_height),
+                ("hgt", self.max_height),
+            ] {
+                if let Some(value) = value {
+                    query.append_pair(key, &value.to_string());
+                }
+            }
+            if let Some(ratio) = self.ratio {
+                query.append_pair(
+                    "ratio",
+                    match ratio {
+                        AspectRatio::Landscape => "0.5",
+                        AspectRatio::Portrait => "-0.5",
+                        AspectRatio::Square => "0",
+                    },
+                );
+            }
+            if let Some(tool) = &self.tool {
+                query.append_pair("tool", tool);
+            }
+            if self.exclude_ai {
+                query.append_pair("ai_type", "1");
+            }
+        }
+        Ok(url)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtworksBody {
+    illust_manga: SearchResults<IllustProfile>,
+}
+
+#[derive(Deserialize)]
+struct IllustrationsBody {
+    illust: SearchResults<IllustProfile>,
+}
+
+#[derive(Deserialize)]
+struct MangaBody {
+    manga: SearchResults<IllustProfile>,
+}
+
+#[derive(Deserialize)]
+struct NovelsBody {
+    novel: SearchResults<NovelProfile>,
+}
+
+impl PixivClient {
+    /// Searches illusts, mangas and ugoiras.
+    pub fn search_artworks(&self, query: &SearchQuery) -> PagedStream<'static, IllustProfile, u32> {
+        let search_type = query.search_type;
+        self.search(
+            "artworks",
+            query,
+            Some(search_type),
+            |body: ArtworksBody| body.illust_manga,
+        )
+    }
+
+    /// Searches illusts and ugoiras.
+    pub fn search_illustrations(
+        &self,
+        query: &SearchQuery,
+    ) -> PagedStream<'static, IllustProfile, u32> {
+        let search_type = match query.search_type {
+            search_type @ (SearchType::Illust | SearchType::Ugoira) => search_type,
+            _ => SearchType::IllustAndUgoira,
+        };
+        self.search(
+            "illustrations",
+            query,
+            Some(search_type),
+            |body: IllustrationsBody| body.illust,
+        )
+    }
+
+    /// Searches mangas.
+    pub fn search_manga(&self, query: &SearchQuery) -> PagedStream<'static, IllustProfile, u32> {
+        self.search(
+            "manga",
+            query,
+            Some(SearchType::Manga),
+            |body: MangaBody| body.manga,
+        )
+    }
+
+    /// Searches novels.
+    pub fn search_novels(&self, query: &SearchQuery) -> PagedStream<'static, NovelProfile, u32> {
+        self.search("novels", query, None, |body: NovelsBody| body.novel)
+    }
+
+    fn search<T, B>(
+        &self,
+        kind: &'static str,
+        query: &SearchQuery,
+        search_type: Option<SearchType>,
+        results: fn(B) -> SearchResults<T>,
+    ) -> PagedStream<'static, T, u32>
+    where
+        T: DeserializeOwned + Send + 'static,
+        B: DeserializeOwned + Send + 'static,
+    {
+        let query = query.clone();
+        let base = self.url(&format!("/ajax/search/{kind}"));
+        let client = self.clone();
+        PagedStream::new(1, move |page| {
+            let client = client.clone();
+            let url = query.url(&base, search_type, page);
+            async move {
+                let SearchResults {
+                    data,
+                    total,
+                    last_page,
+                } = results(client.get::<B>(url?).await?);
+                let next = match last_page {
+                    Some(last_page) if page >= last_page => None,
+                    None if data.is_empty() => None,
+                    _ => Some(page + 1),
+                };
+                Ok(Page {
+                    items: data,
+                    next,
+                    total: Some(total),
+                })
+            }
+        })
+    }
+}
@@ -0,0 +1,95 @@
+//! Manga series endpoints.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+use crate::paginate::{Page, PagedStream};
+use crate::{IllustProfile, MangaSeries, PixivClient, SeriesPage, SeriesWork};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SeriesBody {
+    thumbnails: Thumbnails,
+    illust_series: Vec<MangaSeries>,
+    page: SeriesBodyPage,
+}
+
+#[derive(Deserialize)]
+struct Thumbnails {
+    #[serde(default)]
+    illust: Vec<IllustProfile>,
+}
+
+#[derive(Deserialize)]
+struct SeriesBodyPage {
+    series: Vec<SeriesEntry>,
+    total: usize,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SeriesEntry {
+    #[serde_as(as = "DisplayFromStr")]
+    work_id: i32,
+    order: i32,
+}
+
+impl PixivClient {
+    /// Get a page of the works in a manga series, in series order.
+    ///
+    /// ## Argument
+    /// * `page`: The page to get, starting from 1.
+    pub async fn series(&self, series_id: i32, page: u32) -> crate::Result<SeriesPage> {
+        let body: SeriesBody = self
+            .get(self.url(&format!("/ajax/series/{series_id}?p={page}")))
+            .await?;
+        let series = body
+            .illust_series
+            .into_iter()
+            .find(|series| series.id == series_id)
+            .ok_or_else(|| crate::Error::NotFound(format!("Series {series_id} not found")))?;
+        let mut works: HashMap<i32, IllustProfile> = body
+            .thumbnails
+            .illust
+            .into_iter()
+            .map(|work| (work.id, work))
+            .collect();
+        let works = body
+            .page
+            .series
+            .into_iter()
+            .map(|entry| SeriesWork {
+                id: entry.work_id,
+                order: entry.order,
+                work: works.remove(&entry.work_id),
+            })
+            .collect();
+        Ok(SeriesPage {
+            series,
+            works,
+            total: body.page.total,
+        })
+    }
+
+    /// Get all the works in a manga series as a stream.
+    ///
+    /// The cursor is the page to get and the count of works before it.
+    pub fn series_stream(&self, series_id: i32) -> PagedStream<'static, SeriesWork, (u32, usize)> {
+        let client = self.clone();
+        PagedStream::new((1, 0), move |(page, seen): (u32, usize)| {
+            let client = client.clone();
+            async move {
+                let SeriesPage { works, total, .. } = client.series(series_id, page).await?;
+                let seen = seen + works.len();
+                Ok(Page {
+                    next: (!works.is_empty() && seen < total).then_some((page + 1, seen)),
+                    items: works,
+                    total: Some(total),
+                })
+            }
+        })
+    }
+}
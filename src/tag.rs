@@ -0,0 +1,40 @@
+//! Tag endpoints.
+
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::error::decode_json;
+use crate::{PixivClient, TagInfo, TagSuggestion};
+
+#[derive(Deserialize)]
+struct Candidates {
+    #[serde(default)]
+    candidates: Vec<TagSuggestion>,
+}
+
+impl PixivClient {
+    /// Get info about a tag, including its pixpedia article and translations.
+    pub async fn tag_info(&self, tag: &str) -> crate::Result<TagInfo> {
+        let mut url = Url::parse(&self.url("/ajax/search/tags"))
+            .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"))?;
+        url.path_segments_mut()
+            .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"))?
+            .push(tag);
+        self.get(url).await
+    }
+
+    /// Get the tags suggested for a search prefix, for autocompletion.
+    ///
+    /// ## Argument
+    /// * `lang`: The language of the translations, e.g. `en`.
+    pub async fn tag_suggest(&self, prefix: &str, lang: &str) -> crate::Result<Vec<TagSuggestion>> {
+        let url = Url::parse_with_params(
+            &self.url("/rpc/cps.php"),
+            [("keyword", prefix), ("lang", lang)],
+        )
+        .map_err(|_| crate::Error::InvalidArgument("Base URL seems to be invalid"))?;
+        // This endpoint is not wrapped like the AJAX API.
+        let candidates: Candidates = self.get_json(url, &self.inner.retry, decode_json).await?;
+        Ok(candidates.candidates)
+    }
+}
@@ -3,9 +3,9 @@
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use serde_with::{serde_as, DisplayFromStr};
+use serde_with::{serde_as, DefaultOnNull, DisplayFromStr, PickFirst};
 use std::collections::HashMap;
-use time::OffsetDateTime;
+use time::{OffsetDateTime, PrimitiveDateTime};
 
 /// Illust info.
 #[serde_as]
@@ -46,6 +46,11 @@ pub struct IllustInfo {
     /// Whether the account holder has liked the illust.
     #[serde(rename = "likeData")]
     pub liked: bool,
+    /// The bookmark of the account holder on the illust.
+    pub bookmark_data: Option<BookmarkData>,
+    /// The navigation of the illust in its series.
+    #[serde(rename = "seriesNavData", default)]
+    pub series_nav: Option<SeriesNav>,
     /// The width of the (first) illust.
     pub width: i32,
     /// The height of the (first) illust.
@@ -91,13 +96,49 @@ pub struct UserProfile {
     /// Whether this user has been blocked.
     pub is_blocking: bool,
     /// The description of the user.
+    #[serde(default)]
     pub comment: String,
     #[allow(missing_docs)]
+    #[serde(default)]
     pub followed_back: bool,
     /// Whether this user accept being requested for a work.
     pub accept_request: bool,
 }
 
+/// A preview of a user in a user list, with their latest works.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UserPreview {
+    /// The User ID of the user.
+    #[serde_as(as = "DisplayFromStr")]
+    pub user_id: i32,
+    /// The name of the user.
+    pub user_name: String,
+    /// The profile image URL of the user.
+    pub profile_image_url: String,
+    /// The description of the user.
+    pub user_comment: String,
+    /// Whether you have followed the user.
+    pub following: bool,
+    /// Whether the user has followed you.
+    pub followed: bool,
+    /// Whether this user has been blocked.
+    pub is_blocking: bool,
+    /// Whether this user is in mypixiv.
+    pub is_mypixiv: bool,
+    /// The latest illusts and mangas of the user.
+    #[serde(default)]
+    pub illusts: Vec<IllustProfile>,
+    /// The latest novels of the user.
+    #[serde(default)]
+    pub novels: Vec<NovelProfile>,
+    /// Whether this user accept being requested for a work.
+    #[serde(default)]
+    pub accept_request: bool,
+}
+
 /// Full info about a user.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -152,6 +193,9 @@ pub struct IllustProfile {
     pub page_count: i32,
     /// The profile image URL of the author.
     pub profile_image_url: String,
+    /// The bookmark of the account holder on the illust.
+    #[serde(default)]
+    pub bookmark_data: Option<BookmarkData>,
 }
 
 /// The recent works of an author.
@@ -165,7 +209,9 @@ pub struct UserTopWorks {
     /// Recent mangas.
     #[serde(rename = "manga", deserialize_with = "crate::de::dict_value_to_vec")]
     pub mangas: Vec<IllustProfile>,
-    // TODO: Novels
+    /// Recent novels.
+    #[serde(deserialize_with = "crate::de::dict_value_to_vec")]
+    pub novels: Vec<NovelProfile>,
 }
 
 /// All the works of an author.
@@ -179,7 +225,539 @@ pub struct UserAllWorks {
     /// All manga IDs by the author.
     #[serde(rename = "manga", deserialize_with = "crate::de::dict_key_to_vec")]
     pub mangas: Vec<i32>,
-    // TODO: Novels, Manga Series, Novel Series
+    /// All novel IDs by the author.
+    #[serde(deserialize_with = "crate::de::dict_key_to_vec")]
+    pub novels: Vec<i32>,
+    /// All novel series by the author.
+    pub novel_series: Vec<NovelSeries>,
+    /// All manga series by the author.
+    #[serde(default)]
+    pub manga_series: Vec<MangaSeries>,
+}
+
+/// Novel info.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct NovelInfo {
+    /// The ID of the novel.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i32,
+    /// The title of the novel.
+    pub title: String,
+    /// The description of the novel in HTML format.
+    pub description: String,
+    /// The text of the novel in Pixiv novel markup.
+    pub content: String,
+    /// The URL of the cover image.
+    pub cover_url: String,
+    /// The date the novel is created.
+    #[serde(with = "time::serde::iso8601")]
+    pub create_date: OffsetDateTime,
+    /// The date the novel is uploaded.
+    #[serde(with = "time::serde::iso8601")]
+    pub upload_date: OffsetDateTime,
+    /// The restriction type for the novel.
+    #[serde(rename = "xRestrict")]
+    pub restriction: Restriction,
+    /// The tags infomation of the novel.
+    pub tags: IllustTagsInfo,
+    /// The User ID of the author.
+    #[serde_as(as = "DisplayFromStr")]
+    pub user_id: i32,
+    /// The name of the author.
+    pub user_name: String,
+    /// Whether the account holder has liked the novel.
+    #[serde(rename = "likeData")]
+    pub liked: bool,
+    /// The bookmark of the account holder on the novel.
+    pub bookmark_data: Option<BookmarkData>,
+    /// How many pages the novel have.
+    pub page_count: i32,
+    /// How many characters the novel have.
+    pub character_count: i32,
+    /// How many words the novel have.
+    #[serde(default)]
+    pub word_count: i32,
+    /// How many bookmarks the novel have.
+    pub bookmark_count: i32,
+    /// How many likes the novel have.
+    pub like_count: i32,
+    /// How many comments the novel have.
+    pub comment_count: i32,
+    /// How many views the novel have.
+    pub view_count: i32,
+    /// Whether this novel is original work.
+    #[serde(rename = "isOriginal")]
+    pub original: bool,
+    /// The navigation in the series this novel belongs to.
+    #[serde(rename = "seriesNavData")]
+    pub series_nav: Option<SeriesNav>,
+    /// Images uploaded with the novel, keyed by their ID.
+    #[serde_as(as = "DefaultOnNull")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub text_embedded_images: HashMap<String, NovelEmbeddedImage>,
+}
+
+/// An image uploaded with a novel.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct NovelEmbeddedImage {
+    /// The ID of the image.
+    pub novel_image_id: String,
+    /// The URLs avaliable in the image, keyed by their size.
+    pub urls: HashMap<String, String>,
+}
+
+/// A basic summary of a novel.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct NovelProfile {
+    /// The ID of the novel.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i32,
+    /// The title of the novel.
+    pub title: String,
+    /// The description of the novel in HTML format.
+    pub description: String,
+    /// The restriction type for the novel.
+    #[serde(rename = "xRestrict")]
+    pub restriction: Restriction,
+    /// The URL of the cover image.
+    pub url: String,
+    /// The untranslated tags of the novel.
+    pub tags: Vec<String>,
+    /// The User ID of the author.
+    #[serde_as(as = "DisplayFromStr")]
+    pub user_id: i32,
+    /// The name of the author.
+    pub user_name: String,
+    /// How many characters the novel have.
+    pub text_count: i32,
+    /// How many words the novel have.
+    #[serde(default)]
+    pub word_count: i32,
+    /// How many bookmarks the novel have.
+    #[serde(default)]
+    pub bookmark_count: i32,
+    /// Whether this novel is original work.
+    #[serde(rename = "isOriginal")]
+    pub original: bool,
+    /// The ID of the series this novel belongs to.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub series_id: Option<i32>,
+    /// The title of the series this novel belongs to.
+    #[serde(default)]
+    pub series_title: Option<String>,
+    /// The profile image URL of the author.
+    #[serde(default)]
+    pub profile_image_url: Option<String>,
+    /// The bookmark of the account holder on the novel.
+    #[serde(default)]
+    pub bookmark_data: Option<BookmarkData>,
+}
+
+/// A novel series.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct NovelSeries {
+    /// The ID of the series.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i32,
+    /// The title of the series.
+    pub title: String,
+    /// The caption of the series.
+    pub caption: String,
+    /// The User ID of the author.
+    #[serde_as(as = "DisplayFromStr")]
+    pub user_id: i32,
+    /// The name of the author.
+    pub user_name: String,
+    /// The restriction type for the series.
+    #[serde(rename = "xRestrict")]
+    pub restriction: Restriction,
+    /// The untranslated tags of the series.
+    pub tags: Vec<String>,
+    /// Whether this series is original work.
+    #[serde(rename = "isOriginal")]
+    pub original: bool,
+    /// Whether this series has been concluded.
+    pub is_concluded: bool,
+    /// How many novels have been published in the series.
+    pub published_content_count: i32,
+    /// How many characters have been published in the series.
+    pub published_total_character_count: i32,
+    /// How many words have been published in the series.
+    #[serde(default)]
+    pub published_total_word_count: i32,
+    /// The ID of the first novel in the series.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub first_novel_id: Option<i32>,
+    /// The ID of the latest novel in the series.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub latest_novel_id: Option<i32>,
+    /// The date the series is created.
+    #[serde(with = "time::serde::iso8601")]
+    pub create_date: OffsetDateTime,
+    /// The date the series is updated.
+    #[serde(with = "time::serde::iso8601")]
+    pub update_date: OffsetDateTime,
+}
+
+/// A manga series.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct MangaSeries {
+    /// The ID of the series.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i32,
+    /// The User ID of the author.
+    #[serde_as(as = "DisplayFromStr")]
+    pub user_id: i32,
+    /// The title of the series.
+    pub title: String,
+    /// The description of the series.
+    #[serde(default)]
+    pub description: String,
+    /// The caption of the series.
+    #[serde(default)]
+    pub caption: String,
+    /// How many works have been published in the series.
+    pub total: i32,
+    /// The URL of the cover image.
+    pub url: String,
+    /// The ID of the first work in the series.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub first_illust_id: Option<i32>,
+    /// The ID of the latest work in the series.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub latest_illust_id: Option<i32>,
+    /// The date the series is created.
+    #[serde(with = "time::serde::iso8601")]
+    pub create_date: OffsetDateTime,
+    /// The date the series is updated.
+    #[serde(with = "time::serde::iso8601")]
+    pub update_date: OffsetDateTime,
+    /// Whether the account holder is watching the series.
+    #[serde(default)]
+    pub is_watched: bool,
+}
+
+/// A page of the works in a manga series.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct SeriesPage {
+    /// The series.
+    pub series: MangaSeries,
+    /// The works in this page, in the order of the series.
+    pub works: Vec<SeriesWork>,
+    /// How many works the series have.
+    pub total: usize,
+}
+
+/// A work in a manga series.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct SeriesWork {
+    /// The ID of the work.
+    pub id: i32,
+    /// The order of the work in the series, starting from 1.
+    pub order: i32,
+    /// The summary of the work, `None` if it cannot be viewed.
+    pub work: Option<IllustProfile>,
+}
+
+/// A page of the novels in a series.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct NovelSeriesContents {
+    /// The novels in this page.
+    pub series_contents: Vec<NovelSeriesContent>,
+}
+
+/// A novel in a series.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct NovelSeriesContent {
+    /// The ID of the novel.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i32,
+    /// The title of the novel.
+    pub title: String,
+    /// The position of the novel in the series.
+    pub series: SeriesPosition,
+    /// The restriction type for the novel.
+    #[serde(rename = "xRestrict")]
+    pub restriction: Restriction,
+    /// The URL of the cover image.
+    pub url: String,
+    /// The untranslated tags of the novel.
+    pub tags: Vec<String>,
+    /// How many characters the novel have.
+    pub character_count: i32,
+    /// How many words the novel have.
+    #[serde(default)]
+    pub word_count: i32,
+    /// How many bookmarks the novel have.
+    pub bookmark_count: i32,
+    /// The date the novel is uploaded.
+    #[serde(with = "time::serde::timestamp")]
+    pub upload_timestamp: OffsetDateTime,
+}
+
+/// The position of a work in its series.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SeriesPosition {
+    /// The ID of the series.
+    pub id: i32,
+    /// The order of the work in the series, starting from 1.
+    pub content_order: i32,
+}
+
+/// The navigation of a work in its series.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SeriesNav {
+    /// The ID of the series.
+    #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+    pub series_id: i32,
+    /// The title of the series.
+    pub title: String,
+    /// The order of the work in the series, starting from 1.
+    pub order: i32,
+    /// The previous work in the series.
+    pub prev: Option<SeriesNavWork>,
+    /// The next work in the series.
+    pub next: Option<SeriesNavWork>,
+}
+
+/// A work linked from [`SeriesNav`].
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SeriesNavWork {
+    /// The ID of the work.
+    #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+    pub id: i32,
+    /// The title of the work.
+    pub title: String,
+    /// The order of the work in the series, starting from 1.
+    pub order: i32,
+}
+
+/// The metadata of an ugoira (animated illust).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UgoiraMeta {
+    /// The URL of the frame archive in regular size.
+    pub src: String,
+    /// The URL of the frame archive in original size.
+    pub original_src: String,
+    /// The MIME type of the frames.
+    #[serde(rename = "mime_type")]
+    pub mime_type: String,
+    /// The frames of the ugoira, in order.
+    pub frames: Vec<UgoiraFrame>,
+}
+
+/// A frame of an ugoira.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UgoiraFrame {
+    /// The file name of the frame in the archive.
+    pub file: String,
+    /// How long the frame is shown, in milliseconds.
+    pub delay: u32,
+}
+
+/// A page of search results.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+#[non_exhaustive]
+pub struct SearchResults<T> {
+    /// The works in this page.
+    #[serde(deserialize_with = "crate::de::skip_ad_containers")]
+    pub data: Vec<T>,
+    /// The total count of works matching the query.
+    pub total: usize,
+    /// The last page avaliable.
+    #[serde(default)]
+    pub last_page: Option<u32>,
+}
+
+/// The bookmark of the account holder on a work.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BookmarkData {
+    /// The ID of the bookmark.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i64,
+    /// Whether the bookmark is private.
+    pub private: bool,
+}
+
+/// Options of a bookmark.
+#[derive(Debug, Clone, Default)]
+pub struct BookmarkOptions {
+    /// Who can see the bookmark.
+    pub restrict: Visibility,
+    /// The comment of the bookmark.
+    pub comment: String,
+    /// The tags of the bookmark.
+    pub tags: Vec<String>,
+}
+
+/// Who can see a bookmark or a follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visibility {
+    /// Everyone.
+    #[default]
+    Public,
+    /// Only the account holder.
+    Private,
+}
+
+/// A work in the bookmarks of an user.
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum BookmarkedWork<T> {
+    /// A work that can be viewed.
+    Work(T),
+    /// A work that has been deleted or made private by its author.
+    Masked(MaskedWork),
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for BookmarkedWork<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value.get("isMasked") == Some(&serde_json::Value::Bool(true)) {
+            crate::de::from_value(value).map(BookmarkedWork::Masked)
+        } else {
+            crate::de::from_value(value).map(BookmarkedWork::Work)
+        }
+    }
+}
+
+/// A bookmarked work that can no longer be viewed.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct MaskedWork {
+    /// The ID of the work.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i32,
+    /// The bookmark of the account holder on the work.
+    #[serde(default)]
+    pub bookmark_data: Option<BookmarkData>,
+}
+
+/// A page of the bookmarks of an user.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(bound(deserialize = "T: DeserializeOwned"))]
+#[non_exhaustive]
+pub struct UserBookmarks<T> {
+    /// The bookmarked works.
+    pub works: Vec<BookmarkedWork<T>>,
+    /// The total count of bookmarks matching the filters.
+    pub total: usize,
+}
+
+/// The bookmark tags of an user.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct BookmarkTags {
+    /// Tags used by public bookmarks.
+    pub public: Vec<BookmarkTag>,
+    /// Tags used by private bookmarks.
+    pub private: Vec<BookmarkTag>,
+}
+
+/// A bookmark tag.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct BookmarkTag {
+    /// The name of the tag.
+    pub tag: String,
+    /// How many bookmarks have the tag.
+    #[serde(rename = "cnt")]
+    pub count: usize,
+}
+
+time::serde::format_description!(
+    comment_date,
+    PrimitiveDateTime,
+    "[year]-[month]-[day] [hour]:[minute]"
+);
+
+/// A comment on an illust or a novel.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Comment {
+    /// The ID of the comment.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: i64,
+    /// The User ID of the author.
+    #[serde_as(as = "DisplayFromStr")]
+    pub user_id: i32,
+    /// The name of the author.
+    pub user_name: String,
+    /// The profile image URL of the author.
+    #[serde(rename = "img")]
+    pub profile_image_url: String,
+    /// Whether the author has deleted their account.
+    #[serde(default)]
+    pub is_deleted_user: bool,
+    /// The text of the comment, empty if it is a stamp.
+    pub comment: String,
+    /// The ID of the stamp if the comment is a stamp.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub stamp_id: Option<i32>,
+    /// When the comment was posted, in the time zone of the account holder.
+    #[serde(with = "comment_date")]
+    pub comment_date: PrimitiveDateTime,
+    /// The ID of the root comment if the comment is a reply.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub comment_root_id: Option<i64>,
+    /// The name of the user replied to if the comment is a reply.
+    #[serde(default)]
+    pub reply_to_user_name: Option<String>,
+    /// Whether the account holder can delete the comment.
+    #[serde(default)]
+    pub editable: bool,
+    /// Whether the comment has replies.
+    #[serde(default)]
+    pub has_replies: bool,
 }
 
 /// An image in a illust.
@@ -250,6 +828,82 @@ pub struct IllustTag {
     pub translation: HashMap<String, String>,
 }
 
+/// Info about a tag.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct TagInfo {
+    /// The tag.
+    pub tag: String,
+    /// The Pixiv Encyclopedia (pixpedia) article of the tag, if any.
+    #[serde(default, deserialize_with = "crate::de::empty_array_is_none")]
+    pub pixpedia: Option<Pixpedia>,
+    /// Translations of the tag and its related tags, by tag then by language.
+    #[serde(
+        rename = "tagTranslation",
+        default,
+        deserialize_with = "crate::de::tag_translations"
+    )]
+    pub translations: HashMap<String, HashMap<String, String>>,
+}
+
+impl TagInfo {
+    /// Returns the translation of the tag in `lang`, e.g. `en`.
+    pub fn translation(&self, lang: &str) -> Option<&str> {
+        self.translations
+            .get(&self.tag)?
+            .get(lang)
+            .map(String::as_str)
+    }
+}
+
+/// A Pixiv Encyclopedia (pixpedia) article.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Pixpedia {
+    /// The abstract of the article.
+    #[serde(rename = "abstract", default)]
+    pub summary: Option<String>,
+    /// The representative image of the tag.
+    #[serde(default)]
+    pub image: Option<String>,
+    /// The ID of the representative illust.
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[serde(default)]
+    pub id: Option<i32>,
+    /// The reading of the tag, in hiragana.
+    #[serde(default)]
+    pub yomigana: Option<String>,
+    /// The parent tag.
+    #[serde(default)]
+    pub parent_tag: Option<String>,
+    /// The sibling tags.
+    #[serde(default)]
+    pub siblings_tags: Vec<String>,
+    /// The child tags.
+    #[serde(default)]
+    pub children_tags: Vec<String>,
+}
+
+/// A tag suggested for a search prefix.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct TagSuggestion {
+    /// The tag.
+    #[serde(rename = "tag_name")]
+    pub tag: String,
+    /// How often the tag is searched.
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[serde(default)]
+    pub access_count: Option<u64>,
+    /// The translation of the tag in the requested language.
+    #[serde(rename = "tag_translation", default)]
+    pub translation: Option<String>,
+}
+
 #[allow(missing_docs)]
 #[derive(Serialize_repr, Deserialize_repr, Eq, PartialEq, Debug, Clone, Copy)]
 #[repr(u8)]
@@ -296,6 +950,26 @@ pub struct RankingItem {
     pub view_count: i32,
 }
 
+/// A work in the feed of followed users, with its author.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
+pub struct LatestWork<T> {
+    /// The work.
+    pub work: T,
+    /// The profile of the author, if Pixiv sent it.
+    pub user: Option<UserProfile>,
+}
+
+/// The restriction of works in the feed of followed users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FollowLatestMode {
+    /// All works.
+    #[default]
+    All,
+    /// Only R-18 works.
+    R18,
+}
+
 /// The ranking mode.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -324,20 +998,16 @@ pub enum RankingContent {
     Manga,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub(crate) struct WrappedResponse<T: DeserializeOwned> {
+/// The envelope of an AJAX API response, without its body.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct WrappedResponse {
     pub error: bool,
+    #[serde(default)]
     pub message: String,
-    #[serde(deserialize_with = "crate::de::deserialize_err_is_none")]
-    pub body: Option<T>,
 }
 
-impl<T: DeserializeOwned> From<WrappedResponse<T>> for crate::Result<T> {
-    fn from(val: WrappedResponse<T>) -> Self {
-        if val.error {
-            Result::Err(crate::Error::PixivError(val.message))
-        } else {
-            Result::Ok(val.body.unwrap())
-        }
-    }
+/// The body of a successful AJAX API response.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct WrappedBody<T> {
+    pub body: T,
 }
@@ -0,0 +1,76 @@
+//! Ugoira (animated illust) frames.
+
+#[cfg(feature = "ugoira-encode")]
+pub mod encode;
+
+use std::io::{Cursor, Read};
+
+use futures_util::TryStreamExt;
+
+use crate::{PixivClient, UgoiraFrame, UgoiraMeta};
+
+/// The most memory reserved up front for an archive, in bytes.
+const MAX_PREALLOCATION: u64 = 64 << 20;
+
+/// A frame of an ugoira with its image data.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UgoiraFrameData {
+    /// The file name of the frame in the archive.
+    pub file: String,
+    /// How long the frame is shown, in milliseconds.
+    pub delay: u32,
+    /// The image data of the frame, encoded as [`UgoiraMeta::mime_type`].
+    pub data: Vec<u8>,
+}
+
+/// Reads the frames listed in `frames` from an ugoira archive, in order.
+pub fn read_frames(archive: &[u8], frames: &[UgoiraFrame]) -> crate::Result<Vec<UgoiraFrameData>> {
+    // Pixiv stores the frames uncompressed, so the archive size bounds the
+    // size of a frame unless the archive lies about it.
+    let max_size = archive.len() as u64;
+    let mut archive = zip::ZipArchive::new(Cursor::new(archive))?;
+    frames
+        .iter()
+        .map(|frame| {
+            let mut file = archive.by_name(&frame.file)?;
+            let mut data = Vec::with_capacity(file.size().min(max_size) as usize);
+            file.read_to_end(&mut data)?;
+            Ok(UgoiraFrameData {
+                file: frame.file.clone(),
+                delay: frame.delay,
+                data,
+            })
+        })
+        .collect()
+}
+
+impl PixivClient {
+    /// Downloads the frame archive of an ugoira and returns its frames with
+    /// their delays.
+    ///
+    /// ## Argument
+    /// * `original`: Whether to download the frames in original size.
+    pub async fn ugoira_frames(
+        &self,
+        meta: &UgoiraMeta,
+        original: bool,
+    ) -> crate::Result<Vec<UgoiraFrameData>> {
+        let url = if original {
+            &meta.original_src
+        } else {
+            &meta.src
+        };
+        let mut download = self.download_image(url).await?;
+        // The length is only a hint from the image host, so cap it.
+        let capacity = download
+            .content_length()
+            .unwrap_or(0)
+            .min(MAX_PREALLOCATION);
+        let mut archive = Vec::with_capacity(capacity as usize);
+        while let Some(chunk) = download.try_next().await? {
+            archive.extend_from_slice(&chunk);
+        }
+        read_frames(&archive, &meta.frames)
+    }
+}
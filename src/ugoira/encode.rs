@@ -0,0 +1,228 @@
+//! Encoding ugoira frames to animated images.
+//!
+//! Requires the `ugoira-encode` feature.
+
+use crate::ugoira::UgoiraFrameData;
+
+/// The format of an animated image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Animated GIF.
+    Gif,
+    /// Animated PNG.
+    Apng,
+    /// Animated WebP.
+    WebP,
+}
+
+/// Options to encode an animated image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// The quality of the GIF palette, from 1 (fastest) to 100 (best).
+    ///
+    /// APNG and WebP are always lossless.
+    pub quality: u8,
+    /// How many times the animation plays, `0` to repeat infinitely.
+    pub loop_count: u16,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions {
+            quality: 90,
+            loop_count: 0,
+        }
+    }
+}
+
+/// A decoded frame.
+struct RgbaFrame {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    delay: u32,
+}
+
+fn encode_error(err: impl std::fmt::Display) -> crate::Error {
+    crate::Error::EncodeError(err.to_string())
+}
+
+fn decode_frames(frames: &[UgoiraFrameData]) -> crate::Result<Vec<RgbaFrame>> {
+    if frames.is_empty() {
+        return Err(crate::Error::InvalidArgument("Ugoira has no frames"));
+    }
+    let frames = frames
+        .iter()
+        .map(|frame| {
+            let image = image::load_from_memory(&frame.data)
+                .map_err(encode_error)?
+                .into_rgba8();
+            Ok(RgbaFrame {
+                width: image.width(),
+                height: image.height(),
+                pixels: image.into_raw(),
+                delay: frame.delay,
+            })
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+    let (width, height) = (frames[0].width, frames[0].height);
+    if frames
+        .iter()
+        .any(|frame| frame.width != width || frame.height != height)
+    {
+        return Err(crate::Error::InvalidArgument(
+            "Ugoira frames differ in size",
+        ));
+    }
+    Ok(frames)
+}
+
+/// Encodes the frames of an ugoira to an animated image.
+pub fn encode(
+    frames: &[UgoiraFrameData],
+    format: Format,
+    options: &EncodeOptions,
+) -> crate::Result<Vec<u8>> {
+    match format {
+        Format::Gif => encode_gif(frames, options),
+        Format::Apng => encode_apng(frames, options),
+        Format::WebP => encode_webp(frames, options),
+    }
+}
+
+/// Encodes the frames of an ugoira to an animated GIF.
+///
+/// Frame delays are rounded to 10 milliseconds.
+pub fn encode_gif(frames: &[UgoiraFrameData], options: &EncodeOptions) -> crate::Result<Vec<u8>> {
+    let frames = decode_frames(frames)?;
+    let (Ok(width), Ok(height)) = (
+        u16::try_from(frames[0].width),
+        u16::try_from(frames[0].height),
+    ) else {
+        return Err(crate::Error::InvalidArgument("Ugoira is too large for GIF"));
+    };
+    // Maps quality 1..=100 to the NeuQuant sampling speed 30..=1.
+    let quality = i32::from(options.quality.clamp(1, 100));
+    let speed = 30 - (quality - 1) * 29 / 99;
+    let mut out = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut out, width, height, &[]).map_err(encode_error)?;
+        let repeat = match options.loop_count {
+            0 => gif::Repeat::Infinite,
+            count => gif::Repeat::Finite(count),
+        };
+        encoder.set_repeat(repeat).map_err(encode_error)?;
+        for mut frame in frames {
+            let mut gif_frame =
+                gif::Frame::from_rgba_speed(width, height, &mut frame.pixels, speed);
+            gif_frame.delay = u16::try_from(frame.delay.div_ceil(10)).unwrap_or(u16::MAX);
+            gif_frame.dispose = gif::DisposalMethod::Background;
+            encoder.write_frame(&gif_frame).map_err(encode_error)?;
+        }
+    }
+    Ok(out)
+}
+
+/// Encodes the frames of an ugoira to an animated PNG.
+pub fn encode_apng(frames: &[UgoiraFrameData], options: &EncodeOptions) -> crate::Result<Vec<u8>> {
+    let frames = decode_frames(frames)?;
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, frames[0].width, frames[0].height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(frames.len() as u32, u32::from(options.loop_count))
+        .map_err(encode_error)?;
+    let mut writer = encoder.write_header().map_err(encode_error)?;
+    for frame in frames {
+        let (numerator, denominator) = match u16::try_from(frame.delay) {
+            Ok(delay) => (delay, 1000),
+            Err(_) => (u16::try_from(frame.delay / 100).unwrap_or(u16::MAX), 10),
+        };
+        writer
+            .set_frame_delay(numerator, denominator)
+            .map_err(encode_error)?;
+        writer
+            .write_image_data(&frame.pixels)
+            .map_err(encode_error)?;
+    }
+    writer.finish().map_err(encode_error)?;
+    Ok(out)
+}
+
+/// Encodes the frames of an ugoira to an animated WebP.
+///
+/// Frames are encoded losslessly. Frame delays are capped to about 4.6 hours.
+pub fn encode_webp(frames: &[UgoiraFrameData], options: &EncodeOptions) -> crate::Result<Vec<u8>> {
+    let frames = decode_frames(frames)?;
+    let (width, height) = (frames[0].width, frames[0].height);
+    if width > 1 << 14 || height > 1 << 14 {
+        return Err(crate::Error::InvalidArgument(
+            "Ugoira is too large for WebP",
+        ));
+    }
+
+    let mut chunks = Vec::new();
+    let mut vp8x = vec![0x10 | 0x02, 0, 0, 0];
+    vp8x.extend_from_slice(&u24(width - 1));
+    vp8x.extend_from_slice(&u24(height - 1));
+    write_chunk(&mut chunks, b"VP8X", &vp8x);
+    let mut anim = vec![0, 0, 0, 0];
+    anim.extend_from_slice(&options.loop_count.to_le_bytes());
+    write_chunk(&mut chunks, b"ANIM", &anim);
+
+    for frame in frames {
+        let mut image = Vec::new();
+        image_webp::WebPEncoder::new(&mut image)
+            .encode(&frame.pixels, width, height, image_webp::ColorType::Rgba8)
+            .map_err(encode_error)?;
+        let bitstream = find_chunk(&image, b"VP8L")
+            .ok_or(encode_error("WebP encoder produced no VP8L chunk"))?;
+
+        let mut anmf = Vec::with_capacity(24 + bitstream.len());
+        anmf.extend_from_slice(&u24(0));
+        anmf.extend_from_slice(&u24(0));
+        anmf.extend_from_slice(&u24(width - 1));
+        anmf.extend_from_slice(&u24(height - 1));
+        anmf.extend_from_slice(&u24(frame.delay.min(0xFF_FFFF)));
+        // Do not blend with the previous frame, as each frame is a full image.
+        anmf.push(0x02);
+        write_chunk(&mut anmf, b"VP8L", bitstream);
+        write_chunk(&mut chunks, b"ANMF", &anmf);
+    }
+
+    let mut out = Vec::with_capacity(12 + chunks.len());
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(4 + chunks.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"WEBP");
+    out.extend_from_slice(&chunks);
+    Ok(out)
+}
+
+fn u24(value: u32) -> [u8; 3] {
+    let [a, b, c, _] = value.to_le_bytes();
+    [a, b, c]
+}
+
+fn write_chunk(out: &mut Vec<u8>, name: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(name);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Finds the payload of a chunk in a WebP file.
+fn find_chunk<'a>(webp: &'a [u8], name: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut rest = webp.get(12..)?;
+    while rest.len() >= 8 {
+        let size = u32::from_le_bytes(rest[4..8].try_into().ok()?) as usize;
+        let payload = rest.get(8..8 + size)?;
+        if &rest[..4] == name {
+            return Some(payload);
+        }
+        rest = rest.get(8 + size + size % 2..)?;
+    }
+    None
+}
@@ -0,0 +1,84 @@
+//! Batch lookups of user works.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::paginate::{Page, PagedStream};
+use crate::{IllustProfile, PixivClient, UserAllWorks};
+
+/// How many IDs are looked up at once.
+const CHUNK_SIZE: usize = 48;
+
+#[derive(Deserialize)]
+struct ProfileIllusts {
+    #[serde(deserialize_with = "crate::de::dict_value_to_vec")]
+    works: Vec<IllustProfile>,
+}
+
+impl PixivClient {
+    /// Get the profiles of illusts and mangas by an user from their IDs.
+    ///
+    /// Large lists are looked up in several requests. The profiles are in
+    /// the order of `ids`, and works that cannot be viewed are left out.
+    pub async fn user_illusts_by_ids(
+        &self,
+        user_id: i32,
+        ids: &[i32],
+    ) -> crate::Result<Vec<IllustProfile>> {
+        let mut profiles = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(CHUNK_SIZE) {
+            profiles.extend(self.user_illusts_chunk(user_id, chunk).await?);
+        }
+        Ok(profiles)
+    }
+
+    /// Get the profiles of all illusts and mangas in an [`UserAllWorks`] as a
+    /// stream, newest first.
+    pub fn user_works_stream(
+        &self,
+        user_id: i32,
+        works: &UserAllWorks,
+    ) -> PagedStream<'static, IllustProfile, usize> {
+        let mut ids: Vec<i32> = works.illusts.iter().chain(&works.mangas).copied().collect();
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        ids.dedup();
+        let ids: Arc<[i32]> = ids.into();
+        let total = ids.len();
+        let client = self.clone();
+        PagedStream::new(0, move |offset: usize| {
+            let client = client.clone();
+            let ids = Arc::clone(&ids);
+            async move {
+                let chunk = &ids[offset..(offset + CHUNK_SIZE).min(total)];
+                let items = client.user_illusts_chunk(user_id, chunk).await?;
+                let offset = offset + chunk.len();
+                Ok(Page {
+                    items,
+                    next: (offset < total).then_some(offset),
+                    total: Some(total),
+                })
+            }
+        })
+    }
+
+    async fn user_illusts_chunk(
+        &self,
+        user_id: i32,
+        ids: &[i32],
+    ) -> crate::Result<Vec<IllustProfile>> {
+        let query: String = ids.iter().map(|id| format!("ids[]={id}&")).collect();
+        let url = self.url(&format!(
+            "/ajax/user/{user_id}/profile/illusts?{query}work_category=illustManga&is_first_page=0"
+        ));
+        let mut works: HashMap<i32, IllustProfile> = self
+            .get::<ProfileIllusts>(url)
+            .await?
+            .works
+            .into_iter()
+            .map(|work| (work.id, work))
+            .collect();
+        Ok(ids.iter().filter_map(|id| works.remove(id)).collect())
+    }
+}
@@ -0,0 +1,216 @@
+mod common;
+
+use futures_util::TryStreamExt;
+use pixrs::{BookmarkOptions, BookmarkedWork, PixivClient, Visibility};
+use wiremock::matchers::{body_json, body_string, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::{illust, masked, next_data_page, wrapped};
+
+fn bookmarked(id: i32) -> serde_json::Value {
+    let mut work = illust(id);
+    work["bookmarkData"] = serde_json::json!({ "id": format!("{id}0"), "private": true });
+    work
+}
+
+#[tokio::test]
+async fn user_bookmarks_pages() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    for (offset, works) in [
+        ("0", format!("{},{}", bookmarked(1), masked(2))),
+        ("2", bookmarked(3).to_string()),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/ajax/user/1/illusts/bookmarks"))
+            .and(query_param("tag", "オリジナル"))
+            .and(query_param("offset", offset))
+            .and(query_param("limit", "48"))
+            .and(query_param("rest", "hide"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                wrapped(&format!(
+                    r#"{{"works":[{works}],"total":3,"zoneConfig":{{}}}}"#
+                )),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let works: Vec<_> = client
+        .user_bookmarks(1, Some("オリジナル"), Visibility::Private)
+        .try_collect()
+        .await?;
+    assert_eq!(works.len(), 3);
+    let BookmarkedWork::Work(work) = &works[0] else {
+        panic!("expected a visible work");
+    };
+    assert_eq!(work.id, 1);
+    assert_eq!(work.bookmark_data.as_ref().map(|data| data.id), Some(10));
+    let BookmarkedWork::Masked(work) = &works[1] else {
+        panic!("expected a masked work");
+    };
+    assert_eq!(work.id, 2);
+    assert!(matches!(&works[2], BookmarkedWork::Work(work) if work.id == 3));
+    Ok(())
+}
+
+#[tokio::test]
+async fn user_bookmark_tags() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/user/1/illusts/bookmark/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(
+                r#"{"public":[{"tag":"未分類","cnt":5},{"tag":"オリジナル","cnt":2}],"private":[{"tag":"未分類","cnt":1}],"tooManyBookmark":false,"tooManyBookmarkTags":false}"#,
+            ),
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let tags = client.user_bookmark_tags(1).await?;
+    assert_eq!(tags.public.len(), 2);
+    assert_eq!(tags.public[1].tag, "オリジナル");
+    assert_eq!(tags.public[1].count, 2);
+    assert_eq!(tags.private[0].count, 1);
+    Ok(())
+}
+
+async fn csrf_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(next_data_page("0ab1")))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn add_bookmark_returns_id() -> anyhow::Result<()> {
+    let server = csrf_server().await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/illusts/bookmarks/add"))
+        .and(header("x-csrf-token", "0ab1"))
+        .and(body_json(serde_json::json!({
+            "illust_id": "100412238",
+            "restrict": 1,
+            "comment": "",
+            "tags": ["tag"],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(r#"{"last_bookmark_id":"23456789","stacc_status_id":null}"#),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let options = BookmarkOptions {
+        restrict: Visibility::Private,
+        tags: vec!["tag".to_string()],
+        ..Default::default()
+    };
+    let id = client.add_bookmark(100412238, &options).await?;
+    assert_eq!(id, Some(23456789));
+    Ok(())
+}
+
+#[tokio::test]
+async fn edit_and_delete_illust_bookmark() -> anyhow::Result<()> {
+    let server = csrf_server().await;
+    // Adding an existing bookmark again updates it and returns no ID.
+    Mock::given(method("POST"))
+        .and(path("/ajax/illusts/bookmarks/add"))
+        .and(body_json(serde_json::json!({
+            "illust_id": "100412238",
+            "restrict": 0,
+            "comment": "edited",
+            "tags": ["a", "b"],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(r#"{"last_bookmark_id":null,"stacc_status_id":null}"#),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/illusts/bookmarks/delete"))
+        .and(body_string("bookmark_id=23456789"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(wrapped("[]"), "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let options = BookmarkOptions {
+        restrict: Visibility::Public,
+        comment: "edited".to_string(),
+        tags: vec!["a".to_string(), "b".to_string()],
+    };
+    client.edit_bookmark(100412238, &options).await?;
+    client.delete_bookmark(23456789).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_edit_and_delete_novel_bookmark() -> anyhow::Result<()> {
+    let server = csrf_server().await;
+    let body = serde_json::json!({
+        "novel_id": "20104452",
+        "restrict": 1,
+        "comment": "",
+        "tags": ["tag"],
+    });
+    Mock::given(method("POST"))
+        .and(path("/ajax/novels/bookmarks/add"))
+        .and(body_json(&body))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(wrapped(r#""34567890""#), "application/json"),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/novels/bookmarks/add"))
+        .and(body_json(&body))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(wrapped("null"), "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/novels/bookmarks/delete"))
+        .and(body_string("del=1&book_id=34567890"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(wrapped("[]"), "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let options = BookmarkOptions {
+        restrict: Visibility::Private,
+        tags: vec!["tag".to_string()],
+        ..Default::default()
+    };
+    let id = client.add_novel_bookmark(20104452, &options).await?;
+    assert_eq!(id, Some(34567890));
+    client.edit_novel_bookmark(20104452, &options).await?;
+    client.delete_novel_bookmark(34567890).await?;
+    Ok(())
+}
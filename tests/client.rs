@@ -0,0 +1,192 @@
+mod common;
+
+use pixrs::{PixivClient, RankingContent, RankingMode};
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::{next_data_page, wrapped};
+
+#[tokio::test]
+async fn custom_base_url_and_referer() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238"))
+        .and(header("referer", "https://mirror.example"))
+        .and(header("cookie", "PHPSESSID=token"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(include_str!("json/illust_info_100412238.json")),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .referer("https://mirror.example")
+        .build()?;
+    let info = client.illust_info(100412238).await?;
+    assert_eq!(info.id, 100412238);
+    Ok(())
+}
+
+#[test]
+fn image_url_rewrite() -> anyhow::Result<()> {
+    let client = PixivClient::builder("token")
+        .image_host("http://127.0.0.1:8080/pximg/")
+        .build()?;
+    let url = client
+        .image_url("https://i.pximg.net/img-original/img/2022/08/11/23/46/14/100412238_p0.png")?;
+    assert_eq!(
+        url.as_str(),
+        "http://127.0.0.1:8080/pximg/img-original/img/2022/08/11/23/46/14/100412238_p0.png"
+    );
+    let url = client.image_url("https://s.pximg.net/common/images/no_profile_s.png")?;
+    assert_eq!(url.host_str(), Some("s.pximg.net"));
+    Ok(())
+}
+
+fn ranking_page(illust_id: i32, next: &str) -> String {
+    format!(
+        r#"{{"contents":[{{"title":"t","tags":[],"user_name":"u","profile_img":"","illust_id":{illust_id},"user_id":1,"width":1,"height":1,"view_count":0}}],"prev":false,"next":{next}}}"#
+    )
+}
+
+#[tokio::test]
+async fn ranking_stream_follows_pages() -> anyhow::Result<()> {
+    use futures_util::TryStreamExt;
+
+    let server = MockServer::start().await;
+    for (page, next) in [("1", "2"), ("2", "3"), ("3", "false")] {
+        let illust_id = page.parse()?;
+        Mock::given(method("GET"))
+            .and(path("/ranking.php"))
+            .and(query_param("p", page))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(ranking_page(illust_id, next), "application/json"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let mut stream = client.ranking_stream(RankingMode::Daily, RankingContent::All, None);
+    let mut ids = Vec::new();
+    while let Some(item) = stream.try_next().await? {
+        ids.push(item.illust_id);
+    }
+    assert_eq!(ids, [1, 2, 3]);
+    assert_eq!(stream.cursor(), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn stream_and_request_move_into_tasks() -> anyhow::Result<()> {
+    use futures_util::TryStreamExt;
+
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    assert_shareable::<PixivClient>();
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ranking.php"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(ranking_page(1, "false"), "application/json"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/test"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(wrapped(r#"{"ok":true}"#), "application/json"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let stream = client.ranking_stream(RankingMode::Daily, RankingContent::All, None);
+    let request = client
+        .get::<serde_json::Value>(format!("{}/ajax/test", server.uri()))
+        .into_owned();
+    drop(client);
+    let items = tokio::spawn(stream.try_collect::<Vec<_>>()).await??;
+    assert_eq!(items.len(), 1);
+    let body = tokio::spawn(async move { request.await }).await??;
+    assert_eq!(body["ok"], true);
+    Ok(())
+}
+
+#[tokio::test]
+async fn post_refreshes_csrf_token() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .and(header("cookie", "PHPSESSID=token"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(next_data_page("0ab1")))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(next_data_page("cd23")))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/test"))
+        .and(header("x-csrf-token", "0ab1"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/test"))
+        .and(header("x-csrf-token", "cd23"))
+        .and(body_json(serde_json::json!({ "illust_id": "1" })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(wrapped(r#"{"ok":true}"#), "application/json"),
+        )
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let url = format!("{}/ajax/test", server.uri());
+    for _ in 0..2 {
+        let body: serde_json::Value = client
+            .post(&url)
+            .json(&serde_json::json!({ "illust_id": "1" }))
+            .await?;
+        assert_eq!(body["ok"], true);
+    }
+    assert_eq!(client.csrf_token().await?, "cd23");
+    Ok(())
+}
+
+#[tokio::test]
+async fn clones_share_csrf_token() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(next_data_page("0ab1")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let clone = client.clone();
+    assert_eq!(client.csrf_token().await?, "0ab1");
+    assert_eq!(clone.csrf_token().await?, "0ab1");
+    Ok(())
+}
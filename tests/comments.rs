@@ -0,0 +1,98 @@
+mod common;
+
+use futures_util::TryStreamExt;
+use pixrs::PixivClient;
+use time::macros::datetime;
+use wiremock::matchers::{body_string_contains, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::wrapped;
+
+fn comment(id: i64, stamp_id: Option<i32>, has_replies: bool) -> String {
+    let (comment, stamp_id) = match stamp_id {
+        Some(stamp_id) => (String::new(), format!(r#""{stamp_id}""#)),
+        None => (format!("comment {id}"), "null".to_string()),
+    };
+    format!(
+        r#"{{"userId":"2","userName":"u","isDeletedUser":false,"img":"https://i.pximg.net/user-profile/img/2_50.png","id":"{id}","comment":"{comment}","stampId":{stamp_id},"stampLink":null,"commentDate":"2022-08-12 09:30","commentRootId":null,"commentParentId":null,"commentUserId":"2","replyToUserId":null,"replyToUserName":null,"editable":false,"hasReplies":{has_replies}}}"#
+    )
+}
+
+#[tokio::test]
+async fn illust_comments_pages() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    for (offset, comments, has_next) in [
+        (
+            "0",
+            format!(
+                "{},{}",
+                comment(3, None, true),
+                comment(2, Some(301), false)
+            ),
+            true,
+        ),
+        ("2", comment(1, None, false), false),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/ajax/illusts/comments/roots"))
+            .and(query_param("illust_id", "100412238"))
+            .and(query_param("offset", offset))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                wrapped(&format!(
+                    r#"{{"comments":[{comments}],"hasNext":{has_next}}}"#
+                )),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let comments: Vec<_> = client.illust_comments(100412238).try_collect().await?;
+    let ids: Vec<_> = comments.iter().map(|comment| comment.id).collect();
+    assert_eq!(ids, [3, 2, 1]);
+    assert!(comments[0].has_replies);
+    assert_eq!(comments[0].comment_date, datetime!(2022-08-12 09:30));
+    assert_eq!(comments[1].stamp_id, Some(301));
+    assert_eq!(comments[1].comment, "");
+    Ok(())
+}
+
+#[tokio::test]
+async fn post_comment_returns_id() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let state = r#"{"api":{"token":"0ab1"}}"#;
+    let next_data = serde_json::json!({
+        "props": { "pageProps": { "serverSerializedPreloadedState": state } }
+    });
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<script id="__NEXT_DATA__" type="application/json">{next_data}</script>"#
+        )))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rpc/post_comment.php"))
+        .and(body_string_contains("type=comment"))
+        .and(body_string_contains("illust_id=100412238"))
+        .and(body_string_contains("author_user_id=1"))
+        .and(body_string_contains("parent_id=3"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(r#"{"comment_id":"4","user_id":2,"comment":"hi"}"#),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let id = client.post_comment(100412238, 1, "hi", Some(3)).await?;
+    assert_eq!(id, 4);
+    Ok(())
+}
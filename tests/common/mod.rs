@@ -0,0 +1,36 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use serde_json::Value;
+
+/// Wraps `body` in the envelope of the AJAX API.
+pub fn wrapped(body: &str) -> String {
+    format!(r#"{{"error":false,"message":"","body":{body}}}"#)
+}
+
+/// A Pixiv page embedding `token` as the CSRF token.
+pub fn next_data_page(token: &str) -> String {
+    let state = format!(r#"{{"api":{{"token":"{token}"}},"userData":{{"self":{{"id":"1"}}}}}}"#);
+    let next_data = serde_json::json!({
+        "props": { "pageProps": { "serverSerializedPreloadedState": state } }
+    });
+    format!(
+        r#"<html><head><script id="__NEXT_DATA__" type="application/json">{next_data}</script></head></html>"#
+    )
+}
+
+/// The thumbnail of an illust as found in lists of works, with its ID set to `id`.
+pub fn illust(id: i32) -> Value {
+    with_id(include_str!("../json/illust_profile_98556406.json"), id)
+}
+
+/// The thumbnail of a bookmarked illust that can no longer be viewed.
+pub fn masked(id: i32) -> Value {
+    with_id(include_str!("../json/illust_profile_masked.json"), id)
+}
+
+fn with_id(json: &str, id: i32) -> Value {
+    let mut work: Value = serde_json::from_str(json).expect("fixture is valid JSON");
+    work["id"] = id.to_string().into();
+    work
+}
@@ -1,8 +1,20 @@
-use pixrs::IllustInfo;
+use pixrs::{IllustInfo, NovelInfo};
 
 #[test]
 fn illust_info_deserialize() -> anyhow::Result<()> {
     let data: IllustInfo = serde_json::from_str(include_str!("json/illust_info_100412238.json"))?;
+    assert!(data.series_nav.is_none());
+    println!("{data:#?}");
+    Ok(())
+}
+
+#[test]
+fn novel_info_deserialize() -> anyhow::Result<()> {
+    let data: NovelInfo = serde_json::from_str(include_str!("json/novel_info_20104452.json"))?;
+    let nav = data.series_nav.as_ref().unwrap();
+    assert_eq!(nav.series_id, 10245781);
+    assert_eq!(nav.prev.as_ref().unwrap().id, 20061893);
+    assert_eq!(data.text_embedded_images.len(), 1);
     println!("{data:#?}");
     Ok(())
 }
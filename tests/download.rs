@@ -0,0 +1,68 @@
+use pixrs::PixivClient;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const IMAGE_PATH: &str = "/img-original/img/2022/08/11/23/46/14/100412238_p0.png";
+
+async fn client(server: &MockServer) -> anyhow::Result<PixivClient> {
+    Ok(PixivClient::builder("token")
+        .image_host(server.uri())
+        .build()?)
+}
+
+#[tokio::test]
+async fn download_with_referer() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(IMAGE_PATH))
+        .and(header("referer", "https://www.pixiv.net"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"abcdefg".to_vec()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server).await?;
+    let mut out = Vec::new();
+    let mut last = None;
+    let written = client
+        .download_image_to(
+            &format!("https://i.pximg.net{IMAGE_PATH}"),
+            &mut out,
+            |progress| last = Some(progress),
+        )
+        .await?;
+    assert_eq!(written, 7);
+    assert_eq!(out, b"abcdefg");
+    let last = last.unwrap();
+    assert_eq!(last.downloaded, 7);
+    assert_eq!(last.total, Some(7));
+    Ok(())
+}
+
+#[tokio::test]
+async fn resume_download_to_path() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(IMAGE_PATH))
+        .and(header("range", "bytes=3-"))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header("content-range", "bytes 3-6/7")
+                .set_body_bytes(b"defg".to_vec()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server).await?;
+    let file = std::env::temp_dir().join(format!("pixrs-resume-{}.png", std::process::id()));
+    std::fs::write(&file, b"abc")?;
+    let length = client
+        .download_image_to_path(&format!("https://i.pximg.net{IMAGE_PATH}"), &file, |_| {})
+        .await?;
+    let content = std::fs::read(&file)?;
+    std::fs::remove_file(&file)?;
+    assert_eq!(length, 7);
+    assert_eq!(content, b"abcdefg");
+    Ok(())
+}
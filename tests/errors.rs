@@ -0,0 +1,191 @@
+mod common;
+
+use std::time::Duration;
+
+use futures_util::TryStreamExt;
+use pixrs::search::SearchQuery;
+use pixrs::{Error, PixivClient, RankingContent, RankingMode, Visibility};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::{illust, wrapped};
+
+async fn client(server: &MockServer) -> anyhow::Result<PixivClient> {
+    Ok(PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?)
+}
+
+#[tokio::test]
+async fn status_errors() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/1"))
+        .respond_with(ResponseTemplate::new(404).set_body_raw(
+            r#"{"error":true,"message":"該当作品は削除されたか、存在しない作品IDです。","body":[]}"#,
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/2"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "5"))
+        .mount(&server)
+        .await;
+
+    let client = client(&server).await?;
+    let err = client.illust_info(1).await.expect_err("illust is deleted");
+    assert!(matches!(&err, Error::NotFound(message) if message.contains("削除")));
+    assert!(!err.is_retryable());
+
+    let err = client.illust_info(2).await.expect_err("rate limited");
+    assert!(matches!(err, Error::RateLimited { .. }));
+    assert!(err.is_retryable());
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(5)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn api_message_errors() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/user/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"error":true,"message":"ログインが必要です","body":[]}"#,
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = client(&server).await?;
+    let err = client.user_info(1).await.expect_err("login required");
+    assert!(matches!(err, Error::Unauthorized(_)), "{err:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn deserialize_error_has_path() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ranking.php"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"contents":[{"title":"t","tags":[],"user_name":"u","profile_img":"","illust_id":"oops","user_id":1,"width":1,"height":1,"view_count":0}],"prev":false,"next":false}"#,
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = client(&server).await?;
+    let err = client
+        .ranking(RankingMode::Daily, RankingContent::All, None, None)
+        .await
+        .expect_err("illust_id is not a number");
+    let Error::Deserialize { path, snippet, .. } = err else {
+        panic!("expected a deserialize error, got {err:?}");
+    };
+    assert_eq!(path, "contents[0].illust_id");
+    assert!(snippet.contains(r#""illust_id":"oops""#));
+    Ok(())
+}
+
+#[tokio::test]
+async fn body_schema_drift_is_an_error() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let body = r#"{"error":false,"message":"","body":{"id":"1","title":"t","description":"","illustType":"drawing"}}"#;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+        .mount(&server)
+        .await;
+
+    let client = client(&server).await?;
+    let err = client
+        .illust_info(1)
+        .await
+        .expect_err("illustType is not a number");
+    let Error::Deserialize { path, raw, .. } = err else {
+        panic!("expected a deserialize error, got {err:?}");
+    };
+    assert_eq!(path, "body.illustType");
+    assert_eq!(raw, body);
+    Ok(())
+}
+
+#[tokio::test]
+async fn drift_inside_list_items_has_path() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let mut drifted = illust(2);
+    drifted["illustType"] = "bad".into();
+    let data = format!(r#"[{},{{"isAdContainer":true}},{drifted}]"#, illust(1));
+    Mock::given(method("GET"))
+        .and(path("/ajax/search/artworks/test"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(&format!(
+                r#"{{"illustManga":{{"data":{data},"total":2,"lastPage":1}}}}"#
+            )),
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/user/1/illusts/bookmarks"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(&format!(r#"{{"works":[{drifted}],"total":1}}"#)),
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = client(&server).await?;
+    let err = client
+        .search_artworks(&SearchQuery::new("test"))
+        .try_collect::<Vec<_>>()
+        .await
+        .expect_err("illustType is not a number");
+    let Error::Deserialize { path, .. } = err else {
+        panic!("expected a deserialize error, got {err:?}");
+    };
+    assert_eq!(path, "body.illustManga.data[2].illustType");
+
+    let err = client
+        .user_bookmarks(1, None, Visibility::Public)
+        .try_collect::<Vec<_>>()
+        .await
+        .expect_err("illustType is not a number");
+    let Error::Deserialize { path, message, .. } = err else {
+        panic!("expected a deserialize error, got {err:?}");
+    };
+    assert_eq!(path, "body.works[0]");
+    assert!(message.contains("`illustType`"), "{message}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn drift_inside_id_maps_has_path() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let mut drifted = illust(2);
+    drifted["title"] = 1.into();
+    Mock::given(method("GET"))
+        .and(path("/ajax/user/1/profile/top"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(&format!(
+                r#"{{"illusts":{{"1":{},"2":{drifted}}},"manga":[],"novels":[]}}"#,
+                illust(1)
+            )),
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = client(&server).await?;
+    let err = client
+        .user_top_works(1)
+        .await
+        .expect_err("title is not a string");
+    let Error::Deserialize { path, message, .. } = err else {
+        panic!("expected a deserialize error, got {err:?}");
+    };
+    assert_eq!(path, "body.illusts.2.title");
+    assert!(message.contains("invalid type"), "{message}");
+    Ok(())
+}
@@ -0,0 +1,216 @@
+mod common;
+
+use futures_util::TryStreamExt;
+use pixrs::error::Rejection;
+use pixrs::{Error, FollowLatestMode, PixivClient, Visibility};
+use wiremock::matchers::{body_json, body_string_contains, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::{illust, next_data_page, wrapped};
+
+fn user(id: i32) -> String {
+    format!(
+        r#"{{"userId":"{id}","userName":"u{id}","profileImageUrl":"https://i.pximg.net/user-profile/img/{id}_50.png","userComment":"","following":true,"followed":false,"isBlocking":false,"isMypixiv":false,"illusts":[{{"id":"{id}00","title":"t","illustType":0,"xRestrict":0,"restrict":0,"sl":2,"url":"https://i.pximg.net/c/250x250_80_a2/img-master/img/{id}00_p0_square1200.jpg","description":"","tags":[],"userId":"{id}","userName":"u{id}","width":100,"height":100,"pageCount":1,"isBookmarkable":true,"bookmarkData":null,"alt":"","isMasked":false,"aiType":1,"profileImageUrl":"https://i.pximg.net/user-profile/img/{id}_50.png"}}],"novels":[],"acceptRequest":false}}"#
+    )
+}
+
+#[tokio::test]
+async fn user_following_pages() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    for (offset, users) in [
+        ("0", (1..=24).map(user).collect::<Vec<_>>()),
+        ("24", vec![user(25)]),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/ajax/user/1/following"))
+            .and(query_param("offset", offset))
+            .and(query_param("limit", "24"))
+            .and(query_param("rest", "show"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                wrapped(&format!(
+                    r#"{{"users":[{}],"total":25,"followUserTags":[]}}"#,
+                    users.join(",")
+                )),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let users: Vec<_> = client
+        .user_following(1, Visibility::Public)
+        .try_collect()
+        .await?;
+    assert_eq!(users.len(), 25);
+    assert_eq!(users[24].user_id, 25);
+    assert_eq!(users[0].illusts[0].id, 100);
+    Ok(())
+}
+
+#[tokio::test]
+async fn follow_user_rejected() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(next_data_page("0ab1")))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/bookmark_add.php"))
+        .and(header("x-csrf-token", "0ab1"))
+        .and(body_string_contains("user_id=2"))
+        .and(body_string_contains("restrict=1"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"error":true,"message":"フォローできるユーザー数の上限に達しました"}"#,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rpc_group_setting.php"))
+        .and(body_string_contains("mode=del"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(r#"{"type":"bookuser","id":"2"}"#, "application/json"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let err = client
+        .follow_user(2, Visibility::Private)
+        .await
+        .expect_err("following should be rejected");
+    assert!(matches!(
+        err,
+        Error::Rejected(Rejection::FollowLimitReached)
+    ));
+    client.unfollow_user(2).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn block_user_rejects_html_response() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(next_data_page("0ab1")))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/block/save"))
+        .and(body_json(
+            serde_json::json!({ "user_id": "2", "action": "block" }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html>login</html>"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/block/save"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("[]", "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let err = client
+        .block_user(2)
+        .await
+        .expect_err("an HTML page is not a success");
+    assert!(matches!(err, Error::Deserialize { .. }));
+    client.unblock_user(2).await?;
+    Ok(())
+}
+
+fn illust_by(id: i32, user_id: i32) -> serde_json::Value {
+    let mut work = illust(id);
+    work["userId"] = user_id.to_string().into();
+    work
+}
+
+#[tokio::test]
+async fn follow_latest_resolves_side_tables() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let user = r#"{"userId":"7","name":"u7","image":"https://i.pximg.net/user-profile/img/7_50.png","imageBig":"https://i.pximg.net/user-profile/img/7_170.png","premium":false,"isFollowed":true,"isMypixiv":false,"isBlocking":false,"background":null,"acceptRequest":false}"#;
+    for (page, ids, illusts, last) in [
+        (
+            "1",
+            "[3,1]",
+            format!("{},{}", illust_by(1, 7), illust_by(3, 8)),
+            false,
+        ),
+        ("2", "[2]", illust_by(2, 7).to_string(), true),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/ajax/follow_latest/illust"))
+            .and(query_param("p", page))
+            .and(query_param("mode", "r18"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                wrapped(&format!(
+                    r#"{{"page":{{"ids":{ids},"isLastPage":{last},"tags":[]}},"tagTranslation":[],"thumbnails":{{"illust":[{illusts}],"novel":[],"novelSeries":[],"novelDraft":[],"collection":[]}},"illustSeries":[],"requests":[],"users":[{user}],"zoneConfig":{{}}}}"#
+                )),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let works: Vec<_> = client
+        .follow_latest(FollowLatestMode::R18)
+        .try_collect()
+        .await?;
+    let ids: Vec<_> = works.iter().map(|latest| latest.work.id).collect();
+    assert_eq!(ids, [3, 1, 2]);
+    assert!(works[0].user.is_none());
+    assert_eq!(works[1].user.as_ref().map(|user| user.user_id), Some(7));
+    Ok(())
+}
+
+#[tokio::test]
+async fn follow_latest_continues_past_hidden_pages() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    for (page, ids, illusts, last) in [
+        ("1", "[5,4]", String::new(), false),
+        ("2", "[2]", illust_by(2, 7).to_string(), true),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/ajax/follow_latest/illust"))
+            .and(query_param("p", page))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                wrapped(&format!(
+                    r#"{{"page":{{"ids":{ids},"isLastPage":{last},"tags":[]}},"tagTranslation":[],"thumbnails":{{"illust":[{illusts}],"novel":[],"novelSeries":[],"novelDraft":[],"collection":[]}},"illustSeries":[],"requests":[],"users":[],"zoneConfig":{{}}}}"#
+                )),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let works: Vec<_> = client
+        .follow_latest(FollowLatestMode::All)
+        .try_collect()
+        .await?;
+    let ids: Vec<_> = works.iter().map(|latest| latest.work.id).collect();
+    assert_eq!(ids, [2]);
+    Ok(())
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use pixrs::PixivClient;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::{next_data_page, wrapped};
+
+#[tokio::test]
+async fn like_illust_reports_new_like() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(next_data_page("0ab1")))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/illusts/like"))
+        .and(body_json(serde_json::json!({ "illust_id": "100412238" })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(wrapped(r#"{"is_liked":false}"#), "application/json"),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/ajax/illusts/like"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(wrapped(r#"{"is_liked":true}"#), "application/json"),
+        )
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    assert!(client.like_illust(100412238).await?);
+    assert!(!client.like_illust(100412238).await?);
+    Ok(())
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use pixrs::limit::RateLimit;
+use pixrs::{Error, PixivClient};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::wrapped;
+
+const IMAGE_URL: &str = "https://i.pximg.net/img-original/img/2022/08/11/23/46/14/100412238_p0.png";
+
+#[tokio::test]
+async fn api_rate_limit() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238/ugoira_meta"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(
+                r#"{"src":"a.zip","originalSrc":"b.zip","mime_type":"image/jpeg","frames":[]}"#,
+            ),
+            "application/json",
+        ))
+        .expect(4)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .api_limit(RateLimit::per_second(10.0).burst(2))
+        .build()?;
+    let start = Instant::now();
+    for _ in 0..4 {
+        client.ugoira_meta(100412238).await?;
+    }
+    // 2 requests from the burst, then 2 more at 100ms each.
+    assert!(start.elapsed() >= Duration::from_millis(180));
+    Ok(())
+}
+
+#[tokio::test]
+async fn image_downloads_in_flight() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"abcdefg".to_vec()))
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .image_host(server.uri())
+        .image_limit(RateLimit::unlimited().max_in_flight(1))
+        .build()?;
+    let first = client.download_image(IMAGE_URL).await?;
+    let blocked =
+        tokio::time::timeout(Duration::from_millis(100), client.download_image(IMAGE_URL)).await;
+    assert!(blocked.is_err());
+    drop(first);
+    let second =
+        tokio::time::timeout(Duration::from_secs(5), client.download_image(IMAGE_URL)).await?;
+    assert!(second.is_ok());
+    Ok(())
+}
+
+#[tokio::test]
+async fn budgets_are_separate() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238/ugoira_meta"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(
+                r#"{"src":"a.zip","originalSrc":"b.zip","mime_type":"image/jpeg","frames":[]}"#,
+            ),
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(
+            "/img-original/img/2022/08/11/23/46/14/100412238_p0.png",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"abcdefg".to_vec()))
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .image_host(server.uri())
+        .image_limit(RateLimit::unlimited().max_in_flight(1))
+        .build()?;
+    let _download = client.download_image(IMAGE_URL).await?;
+    let meta = tokio::time::timeout(Duration::from_secs(5), client.ugoira_meta(100412238)).await?;
+    assert_eq!(meta?.src, "a.zip");
+    Ok(())
+}
+
+#[test]
+fn invalid_rate_is_rejected() {
+    for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let result = PixivClient::builder("token")
+            .api_limit(RateLimit::per_second(rate))
+            .build();
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "{rate}");
+    }
+}
@@ -0,0 +1,126 @@
+use pixrs::novel::markup::{Document, Image, Node};
+use pixrs::NovelInfo;
+
+#[test]
+fn parse_tags() {
+    let doc = Document::parse(
+        "[chapter:一 [[rb:灯 > ひ]]]\n[[rb:灯台 > とうだい]]へ\n[pixivimage:100412238-2]\n[newpage]\n[[jumpuri:Link > https://example.com/]][jump:1]\n[uploadedimage:15071258]",
+    );
+    assert_eq!(doc.pages.len(), 2);
+    assert_eq!(
+        doc.pages[0].nodes,
+        [
+            Node::Chapter(vec![
+                Node::Text("一 ".to_string()),
+                Node::Ruby {
+                    base: "灯".to_string(),
+                    reading: "ひ".to_string()
+                }
+            ]),
+            Node::Ruby {
+                base: "灯台".to_string(),
+                reading: "とうだい".to_string()
+            },
+            Node::Text("へ".to_string()),
+            Node::Newline,
+            Node::Image(Image::Pixiv {
+                illust_id: 100412238,
+                page: Some(2)
+            }),
+        ]
+    );
+    assert_eq!(
+        doc.pages[1].nodes,
+        [
+            Node::Link {
+                title: "Link".to_string(),
+                url: "https://example.com/".to_string()
+            },
+            Node::Jump(1),
+            Node::Newline,
+            Node::Image(Image::Uploaded {
+                image_id: "15071258".to_string()
+            }),
+        ]
+    );
+}
+
+#[test]
+fn malformed_tags_are_text() {
+    let doc = Document::parse("[chapter:unclosed\n[jump:x] [[rb:no separator]]");
+    assert_eq!(
+        doc.pages[0].nodes,
+        [
+            Node::Text("[chapter:unclosed".to_string()),
+            Node::Newline,
+            Node::Text("[jump:x] [[rb:no separator]]".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn render_html_is_sanitized() {
+    let doc = Document::parse(
+        "<script>alert(1)</script>\n[[jumpuri:bad > javascript:alert(1)]][[jumpuri:good > https://example.com/?a=1&b=2]]",
+    );
+    let html = doc.to_html();
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&lt;script&gt;"));
+    assert!(!html.contains("javascript:"));
+    assert!(html.contains(r#"<a href="https://example.com/?a=1&amp;b=2""#));
+}
+
+#[test]
+fn chapter_title_is_text_and_ruby() {
+    let doc = Document::parse("[chapter:[[jumpuri:a > https://example.com/]][[rb:灯 > ひ]]]");
+    assert_eq!(
+        doc.pages[0].nodes,
+        [Node::Chapter(vec![
+            Node::Text("[[jumpuri:a > https://example.com/]]".to_string()),
+            Node::Ruby {
+                base: "灯".to_string(),
+                reading: "ひ".to_string()
+            },
+        ])]
+    );
+}
+
+#[test]
+fn render_markdown_escapes_blocks() {
+    let doc = Document::parse("1. not a list\n- nor this\n---\n+ or\n=\n    code\n2024年");
+    let markdown = doc.to_markdown();
+    assert_eq!(
+        markdown,
+        "<a id=\"page-1\"></a>\n\n1\\. not a list\n\n\\- nor this\n\n\\---\n\n\\+ or\n\n\\=\n\n&#32;   code\n\n2024年\n"
+    );
+}
+
+#[test]
+fn render_markdown_escapes_entities() {
+    let doc = Document::parse("&lt;b&gt; &amp; &#32;");
+    let markdown = doc.to_markdown();
+    assert_eq!(
+        markdown,
+        "<a id=\"page-1\"></a>\n\n\\&lt;b\\&gt; \\&amp; \\&\\#32;\n"
+    );
+}
+
+#[test]
+fn render_novel() -> anyhow::Result<()> {
+    let info: NovelInfo = serde_json::from_str(include_str!("json/novel_info_20104452.json"))?;
+    let doc = info.document();
+    assert_eq!(doc.pages.len(), 2);
+
+    let html = doc.render_html(|image| info.image_url(image));
+    assert!(html.contains(r#"<section class="novel-page" id="page-2">"#));
+    assert!(html.contains("<ruby>灯台<rp>(</rp><rt>とうだい</rt><rp>)</rp></ruby>"));
+    assert!(html.contains("tei15071258.jpg"));
+
+    let markdown = doc.render_markdown(|image| info.image_url(image));
+    assert!(markdown.contains("## 一　灯"));
+    assert!(markdown.contains("灯台（とうだい）"));
+    assert!(markdown.contains("[illust 100412238](https://www.pixiv.net/artworks/100412238#1)"));
+    assert!(markdown.contains("[こちら](https://www.pixiv.net/)"));
+    assert!(markdown.contains("[Page 1](#page-1)"));
+    Ok(())
+}
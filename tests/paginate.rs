@@ -0,0 +1,100 @@
+use futures_util::TryStreamExt;
+use pixrs::paginate::{Offset, Page, PagedStream, Position};
+
+fn numbers(total: usize) -> PagedStream<'static, usize, Offset> {
+    PagedStream::new(Offset::new(3), move |cursor: Offset| async move {
+        let end = (cursor.offset + cursor.limit).min(total);
+        let items: Vec<usize> = (cursor.offset..end).collect();
+        Ok(Page {
+            next: cursor.next_page(items.len(), Some(total)),
+            items,
+            total: Some(total),
+        })
+    })
+}
+
+#[tokio::test]
+async fn offset_pages() -> anyhow::Result<()> {
+    let mut stream = numbers(7);
+    let mut items = Vec::new();
+    while let Some(item) = stream.try_next().await? {
+        items.push(item);
+    }
+    assert_eq!(items, (0..7).collect::<Vec<_>>());
+    assert_eq!(stream.total(), Some(7));
+    assert_eq!(stream.cursor(), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn resume_from_cursor() -> anyhow::Result<()> {
+    let mut stream = numbers(7);
+    for _ in 0..3 {
+        stream.try_next().await?;
+    }
+    let position = stream.cursor().unwrap();
+    assert_eq!(position.cursor.offset, 3);
+    assert_eq!(position.skip, 0);
+
+    let items: Vec<usize> = numbers(7).resume_from(position).try_collect().await?;
+    assert_eq!(items, (3..7).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[tokio::test]
+async fn resume_inside_page() -> anyhow::Result<()> {
+    let mut stream = numbers(8);
+    stream.try_next().await?;
+    let position = stream.cursor().unwrap();
+    assert_eq!(position.cursor.offset, 0);
+    assert_eq!(position.skip, 1);
+
+    let mut resumed = numbers(8).resume_from(position);
+    assert_eq!(resumed.total(), None);
+    let items: Vec<usize> = (&mut resumed).try_collect().await?;
+    assert_eq!(items, (1..8).collect::<Vec<_>>());
+    assert_eq!(resumed.total(), Some(8));
+
+    let position = Position {
+        cursor: Offset {
+            offset: 3,
+            limit: 3,
+        },
+        skip: 2,
+    };
+    let items: Vec<usize> = numbers(8).resume_from(position).try_collect().await?;
+    assert_eq!(items, [5, 6, 7]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_page_does_not_end() -> anyhow::Result<()> {
+    let stream = PagedStream::new(0, |page: usize| async move {
+        Ok(Page {
+            items: if page == 1 { Vec::new() } else { vec![page] },
+            next: (page < 3).then_some(page + 1),
+            total: None,
+        })
+    });
+    let items: Vec<usize> = stream.try_collect().await?;
+    assert_eq!(items, [0, 2, 3]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn borrowing_stream() -> anyhow::Result<()> {
+    let words = vec!["a", "b", "c"];
+    let stream = PagedStream::new(0, |page: usize| {
+        let words = &words;
+        async move {
+            Ok(Page {
+                items: words.get(page).into_iter().copied().collect(),
+                next: (page + 1 < words.len()).then_some(page + 1),
+                total: Some(words.len()),
+            })
+        }
+    });
+    let items: Vec<&str> = stream.try_collect().await?;
+    assert_eq!(items, words);
+    Ok(())
+}
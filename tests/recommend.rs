@@ -0,0 +1,95 @@
+mod common;
+
+use futures_util::TryStreamExt;
+use pixrs::recommend::DiscoveryMode;
+use pixrs::PixivClient;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::{illust, wrapped};
+
+#[tokio::test]
+async fn illust_recommend_fetches_next_ids() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let next_ids: Vec<String> = (2..=40).map(|id| format!(r#""{id}""#)).collect();
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238/recommend/init"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(&format!(
+                r#"{{"illusts":[{},{{"isAdContainer":true}}],"nextIds":[{}],"details":{{}}}}"#,
+                illust(1),
+                next_ids.join(",")
+            )),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // The first batch only has illusts that can no longer be viewed.
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/recommend/illusts"))
+        .and(query_param("illust_ids[]", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(wrapped(r#"{"illusts":[]}"#), "application/json"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/recommend/illusts"))
+        .and(query_param("illust_ids[]", "20"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(&format!(r#"{{"illusts":[{},{}]}}"#, illust(20), illust(21))),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/recommend/illusts"))
+        .and(query_param("illust_ids[]", "38"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(&format!(r#"{{"illusts":[{}]}}"#, illust(40))),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let ids: Vec<_> = client
+        .illust_recommend(100412238)
+        .map_ok(|illust| illust.id)
+        .try_collect()
+        .await?;
+    assert_eq!(ids, [1, 20, 21, 40]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn discovery_returns_thumbnails() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/discovery/artworks"))
+        .and(query_param("mode", "safe"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(&format!(
+                r#"{{"thumbnails":{{"illust":[{},{}],"novel":[]}},"recommendedIllusts":[]}}"#,
+                illust(1),
+                illust(2)
+            )),
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let illusts = client.discovery(DiscoveryMode::Safe).await?;
+    assert_eq!(illusts.len(), 2);
+    Ok(())
+}
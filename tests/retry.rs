@@ -0,0 +1,191 @@
+mod common;
+
+use std::time::Duration;
+
+use pixrs::retry::RetryPolicy;
+use pixrs::{Error, PixivClient};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::wrapped;
+
+fn policy() -> RetryPolicy {
+    RetryPolicy::default()
+        .max_attempts(3)
+        .backoff(Duration::from_millis(1), Duration::from_millis(10))
+        .jitter(false)
+}
+
+#[tokio::test]
+async fn get_retries_transient_errors() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238/ugoira_meta"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238/ugoira_meta"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238/ugoira_meta"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(
+                r#"{"src":"a.zip","originalSrc":"b.zip","mime_type":"image/jpeg","frames":[]}"#,
+            ),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .retry(policy())
+        .build()?;
+    let meta = client.ugoira_meta(100412238).await?;
+    assert_eq!(meta.src, "a.zip");
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_gives_up() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/1"))
+        .respond_with(ResponseTemplate::new(502))
+        .expect(3)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/2"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .retry(policy())
+        .build()?;
+    let err = client.illust_info(1).await.expect_err("always failing");
+    assert!(err.is_retryable());
+    let err = client.illust_info(2).await.expect_err("not found");
+    assert!(matches!(err, Error::NotFound(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn per_request_override() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/1"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    client.illust_info(1).await.expect_err("not retried");
+    client
+        .illust_info(1)
+        .retry(policy().max_attempts(1))
+        .await
+        .expect_err("not retried either");
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_retries() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/img-original/img/1_p0.png"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/img-original/img/1_p0.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"png".to_vec()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .image_host(server.uri())
+        .retry(policy())
+        .build()?;
+    let mut data = Vec::new();
+    client
+        .download_image_to(
+            "https://i.pximg.net/img-original/img/1_p0.png",
+            &mut data,
+            |_| {},
+        )
+        .await?;
+    assert_eq!(data, b"png");
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_retries_errors_in_body() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238/ugoira_meta"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"error":true,"message":"Too many requests","body":[]}"#,
+            "application/json",
+        ))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/100412238/ugoira_meta"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            wrapped(
+                r#"{"src":"a.zip","originalSrc":"b.zip","mime_type":"image/jpeg","frames":[]}"#,
+            ),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .retry(policy())
+        .build()?;
+    let meta = client.ugoira_meta(100412238).await?;
+    assert_eq!(meta.src, "a.zip");
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_after_is_capped() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/1"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "3600"))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .retry(policy())
+        .build()?;
+    let result = tokio::time::timeout(Duration::from_secs(5), client.illust_info(1)).await?;
+    let err = result.expect_err("always rate limited");
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(3600)));
+    Ok(())
+}
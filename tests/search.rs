@@ -0,0 +1,56 @@
+mod common;
+
+use futures_util::TryStreamExt;
+use pixrs::search::{AspectRatio, SearchMatch, SearchQuery};
+use pixrs::PixivClient;
+use wiremock::matchers::{method, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::illust;
+
+#[tokio::test]
+async fn search_artworks_pages() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let word = "アークナイツ (ウタゲ OR シデロカ) -AI生成";
+    for (page, data) in [
+        (
+            "1",
+            format!(r#"{},{{"isAdContainer":true}},{}"#, illust(1), illust(2)),
+        ),
+        ("2", illust(3).to_string()),
+    ] {
+        let body = format!(
+            r#"{{"error":false,"message":"","body":{{"illustManga":{{"data":[{data}],"total":3,"lastPage":2}},"popular":{{"recent":[],"permanent":[]}},"relatedTags":[]}}}}"#
+        );
+        Mock::given(method("GET"))
+            .and(path_regex("^/ajax/search/artworks/[^/]+$"))
+            .and(query_param("word", word))
+            .and(query_param("p", page))
+            .and(query_param("s_mode", "s_tag_full"))
+            .and(query_param("ratio", "-0.5"))
+            .and(query_param("ai_type", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let query = SearchQuery::new("アークナイツ")
+        .or(["ウタゲ", "シデロカ"])
+        .exclude("AI生成")
+        .match_mode(SearchMatch::ExactTag)
+        .ratio(AspectRatio::Portrait)
+        .exclude_ai(true);
+    assert_eq!(query.word(), word);
+    let mut stream = client.search_artworks(&query);
+    let mut ids = Vec::new();
+    while let Some(illust) = stream.try_next().await? {
+        ids.push(illust.id);
+    }
+    assert_eq!(ids, [1, 2, 3]);
+    assert_eq!(stream.total(), Some(3));
+    Ok(())
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use futures_util::TryStreamExt;
+use pixrs::PixivClient;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::illust;
+
+fn series_page(entries: &str, illusts: &str) -> String {
+    format!(
+        r#"{{"error":false,"message":"","body":{{"tagTranslation":[],"thumbnails":{{"illust":[{illusts}],"novel":[],"novelSeries":[],"novelDraft":[],"collection":[]}},"illustSeries":[{{"id":"5","userId":"1","title":"s","description":"","caption":"","total":3,"content_order":null,"url":"https://i.pximg.net/c/240x480_80/img-master/img/10_p0_master1200.jpg","coverImageSl":2,"firstIllustId":"10","latestIllustId":"30","createDate":"2022-08-11T23:46:14+09:00","updateDate":"2022-09-11T23:46:14+09:00","watchCount":null,"isWatched":true,"isNotifying":false}}],"requests":[],"users":[],"page":{{"series":[{entries}],"isSetCover":false,"seriesId":5,"otherSeriesId":"0","recentUpdatedWorkIds":[30],"total":3,"isWatched":true,"isNotifying":false}}}}}}"#
+    )
+}
+
+#[tokio::test]
+async fn series_resolves_works() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    // Page 1 is fetched by both `series` and `series_stream`.
+    for (page, entries, illusts, expect) in [
+        (
+            "1",
+            r#"{"workId":"10","order":1},{"workId":"20","order":2}"#,
+            format!("{},{}", illust(20), illust(10)),
+            2,
+        ),
+        ("2", r#"{"workId":"30","order":3}"#, String::new(), 1),
+        ("3", "", String::new(), 0),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/ajax/series/5"))
+            .and(query_param("p", page))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(series_page(entries, &illusts), "application/json"),
+            )
+            .expect(expect)
+            .mount(&server)
+            .await;
+    }
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let page = client.series(5, 1).await?;
+    assert_eq!(page.series.title, "s");
+    assert!(page.series.is_watched);
+    assert_eq!(page.total, 3);
+    assert_eq!(page.works[0].work.as_ref().map(|work| work.id), Some(10));
+
+    let works: Vec<_> = client.series_stream(5).try_collect().await?;
+    let orders: Vec<_> = works.iter().map(|work| work.order).collect();
+    assert_eq!(orders, [1, 2, 3]);
+    assert!(works[2].work.is_none());
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use pixrs::PixivClient;
+use wiremock::matchers::{method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn tag_info_pixpedia_and_translations() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path_regex("^/ajax/search/tags/[^/]+$"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"error":false,"message":"","body":{"tag":"アークナイツ","word":"アークナイツ","pixpedia":{"abstract":"Yostarのゲーム。","image":"https://embed.pixiv.net/decorate.php?illust_id=1","id":"1","yomigana":"あーくないつ","parentTag":"スマホゲーム","siblingsTags":["ブルーアーカイブ"],"childrenTags":["ウタゲ(アークナイツ)"]},"breadcrumbs":{"current":[],"parent":[]},"myFavoriteTags":[],"tagTranslation":{"アークナイツ":{"en":"Arknights","ko":"","zh":"明日方舟","zh_tw":"","romaji":"a-kunaitsu"}}}}"#,
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let info = client.tag_info("アークナイツ").await?;
+    let pixpedia = info.pixpedia.as_ref().expect("pixpedia article");
+    assert_eq!(pixpedia.summary.as_deref(), Some("Yostarのゲーム。"));
+    assert_eq!(pixpedia.parent_tag.as_deref(), Some("スマホゲーム"));
+    assert_eq!(pixpedia.children_tags, ["ウタゲ(アークナイツ)"]);
+    assert_eq!(info.translation("en"), Some("Arknights"));
+    assert_eq!(info.translation("ko"), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn tag_info_without_pixpedia() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/search/tags/abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"error":false,"message":"","body":{"tag":"abc","word":"abc","pixpedia":[],"breadcrumbs":[],"myFavoriteTags":[],"tagTranslation":[]}}"#,
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let info = client.tag_info("abc").await?;
+    assert!(info.pixpedia.is_none());
+    assert!(info.translations.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn tag_suggest_candidates() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rpc/cps.php"))
+        .and(query_param("keyword", "アーク"))
+        .and(query_param("lang", "en"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"candidates":[{"tag_name":"アークナイツ","access_count":"123456","tag_translation":"Arknights","type":"tag_translation"},{"tag_name":"アークナイツ10000users入り","access_count":"42","type":"prefix"}]}"#,
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let suggestions = client.tag_suggest("アーク", "en").await?;
+    assert_eq!(suggestions.len(), 2);
+    assert_eq!(suggestions[0].access_count, Some(123456));
+    assert_eq!(suggestions[0].translation.as_deref(), Some("Arknights"));
+    assert_eq!(suggestions[1].translation, None);
+    Ok(())
+}
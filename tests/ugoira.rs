@@ -0,0 +1,73 @@
+use std::io::Write;
+
+use pixrs::PixivClient;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use zip::write::SimpleFileOptions;
+
+fn archive(files: &[(&str, &[u8])]) -> anyhow::Result<Vec<u8>> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, data) in files {
+        writer.start_file(*name, SimpleFileOptions::default())?;
+        writer.write_all(data)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+#[tokio::test]
+async fn ugoira_frames_in_order() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let meta = r#"{"error":false,"message":"","body":{"src":"https://i.pximg.net/img-zip-ugoira/img/1_ugoira600x600.zip","originalSrc":"https://i.pximg.net/img-zip-ugoira/img/1_ugoira1920x1080.zip","mime_type":"image/jpeg","frames":[{"file":"000000.jpg","delay":100},{"file":"000001.jpg","delay":250}]}}"#;
+    Mock::given(method("GET"))
+        .and(path("/ajax/illust/1/ugoira_meta"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(meta, "application/json"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/img-zip-ugoira/img/1_ugoira1920x1080.zip"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(archive(&[
+            ("000001.jpg", b"second"),
+            ("000000.jpg", b"first"),
+        ])?))
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .image_host(server.uri())
+        .build()?;
+    let meta = client.ugoira_meta(1).await?;
+    assert_eq!(meta.mime_type, "image/jpeg");
+    let frames = client.ugoira_frames(&meta, true).await?;
+    let frames: Vec<_> = frames
+        .iter()
+        .map(|frame| (frame.data.as_slice(), frame.delay))
+        .collect();
+    assert_eq!(frames, [(&b"first"[..], 100), (&b"second"[..], 250)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn huge_content_length_is_an_error() -> anyhow::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Claims a petabyte, then closes the connection.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let host = format!("http://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await?;
+        let mut request = [0; 4096];
+        let _ = socket.read(&mut request).await?;
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1099511627776000\r\n\r\nPK")
+            .await?;
+        anyhow::Ok(())
+    });
+
+    let client = PixivClient::builder("token").image_host(host).build()?;
+    let meta: pixrs::UgoiraMeta = serde_json::from_str(
+        r#"{"src":"https://i.pximg.net/img-zip-ugoira/img/1_ugoira600x600.zip","originalSrc":"https://i.pximg.net/img-zip-ugoira/img/1_ugoira1920x1080.zip","mime_type":"image/jpeg","frames":[]}"#,
+    )?;
+    assert!(client.ugoira_frames(&meta, false).await.is_err());
+    Ok(())
+}
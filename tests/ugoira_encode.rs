@@ -0,0 +1,78 @@
+#![cfg(feature = "ugoira-encode")]
+
+use std::io::{Cursor, Write};
+
+use pixrs::ugoira::encode::{encode, EncodeOptions, Format};
+use pixrs::ugoira::{read_frames, UgoiraFrameData};
+use pixrs::UgoiraFrame;
+use zip::write::SimpleFileOptions;
+
+fn png_frame(color: [u8; 4]) -> anyhow::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, 4, 4);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&color.repeat(16))?;
+    writer.finish()?;
+    Ok(out)
+}
+
+fn frames() -> anyhow::Result<Vec<UgoiraFrameData>> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, color) in [
+        ("000000.png", [255, 0, 0, 255]),
+        ("000001.png", [0, 0, 255, 255]),
+    ] {
+        writer.start_file(name, SimpleFileOptions::default())?;
+        writer.write_all(&png_frame(color)?)?;
+    }
+    let archive = writer.finish()?.into_inner();
+    let meta: Vec<UgoiraFrame> = serde_json::from_str(
+        r#"[{"file":"000000.png","delay":100},{"file":"000001.png","delay":250}]"#,
+    )?;
+    Ok(read_frames(&archive, &meta)?)
+}
+
+#[test]
+fn encode_gif() -> anyhow::Result<()> {
+    let gif = encode(&frames()?, Format::Gif, &EncodeOptions::default())?;
+    let mut decoder = gif::DecodeOptions::new().read_info(gif.as_slice())?;
+    let mut delays = Vec::new();
+    while let Some(frame) = decoder.read_next_frame()? {
+        delays.push(frame.delay);
+    }
+    assert_eq!(delays, [10, 25]);
+    Ok(())
+}
+
+#[test]
+fn encode_apng() -> anyhow::Result<()> {
+    let apng = encode(&frames()?, Format::Apng, &EncodeOptions::default())?;
+    let reader = png::Decoder::new(Cursor::new(apng)).read_info()?;
+    let control = reader.info().animation_control.unwrap();
+    assert_eq!(control.num_frames, 2);
+    assert_eq!(control.num_plays, 0);
+    Ok(())
+}
+
+#[test]
+fn encode_webp() -> anyhow::Result<()> {
+    let webp = encode(&frames()?, Format::WebP, &EncodeOptions::default())?;
+    let mut decoder = image_webp::WebPDecoder::new(Cursor::new(webp))?;
+    assert!(decoder.is_animated());
+    assert_eq!(decoder.num_frames(), 2);
+    assert_eq!(decoder.dimensions(), (4, 4));
+    let mut buf = vec![0; decoder.output_buffer_size().unwrap()];
+    let mut delays = Vec::new();
+    let mut first = Vec::new();
+    for _ in 0..2 {
+        delays.push(decoder.read_frame(&mut buf)?);
+        if first.is_empty() {
+            first = buf.clone();
+        }
+    }
+    assert_eq!(delays, [100, 250]);
+    assert_eq!(&first[..4], [255, 0, 0, 255]);
+    Ok(())
+}
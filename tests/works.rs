@@ -0,0 +1,77 @@
+mod common;
+
+use futures_util::TryStreamExt;
+use pixrs::{PixivClient, UserAllWorks};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+use common::{illust, wrapped};
+
+/// Answers with the requested works, leaving out IDs divisible by 10.
+fn respond(request: &Request) -> ResponseTemplate {
+    let works: serde_json::Map<String, serde_json::Value> = request
+        .url
+        .query_pairs()
+        .filter(|(key, _)| key == "ids[]")
+        .filter_map(|(_, id)| id.parse::<i32>().ok())
+        .filter(|id| id % 10 != 0)
+        .map(|id| (id.to_string(), illust(id)))
+        .collect();
+    // Pixiv sends an empty array instead of an empty object.
+    let works = if works.is_empty() {
+        "[]".to_string()
+    } else {
+        serde_json::Value::Object(works).to_string()
+    };
+    ResponseTemplate::new(200).set_body_raw(
+        wrapped(&format!(r#"{{"works":{works},"extraData":{{}}}}"#)),
+        "application/json",
+    )
+}
+
+#[tokio::test]
+async fn user_illusts_by_ids_chunks() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/user/1/profile/illusts"))
+        .and(query_param("work_category", "illustManga"))
+        .respond_with(respond)
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let ids: Vec<i32> = (1..=50).rev().collect();
+    let profiles = client.user_illusts_by_ids(1, &ids).await?;
+    let expected: Vec<i32> = ids.iter().copied().filter(|id| id % 10 != 0).collect();
+    let fetched: Vec<i32> = profiles.iter().map(|profile| profile.id).collect();
+    assert_eq!(fetched, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn user_works_stream_newest_first() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ajax/user/1/profile/illusts"))
+        .respond_with(respond)
+        .mount(&server)
+        .await;
+
+    let works: UserAllWorks = serde_json::from_str(
+        r#"{"illusts":{"3":null,"10":null,"20":null},"manga":{"5":null},"novels":[],"novelSeries":[],"mangaSeries":[]}"#,
+    )?;
+    let client = PixivClient::builder("token")
+        .base_url(server.uri())
+        .build()?;
+    let mut stream = client.user_works_stream(1, &works);
+    let mut ids = Vec::new();
+    while let Some(profile) = stream.try_next().await? {
+        ids.push(profile.id);
+    }
+    assert_eq!(ids, [5, 3]);
+    assert_eq!(stream.total(), Some(4));
+    Ok(())
+}